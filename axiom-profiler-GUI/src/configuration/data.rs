@@ -1,6 +1,6 @@
 use fxhash::FxHashMap;
 use smt_log_parser::{
-    display_with::{DisplayConfiguration, SymbolReplacement},
+    display_with::{DisplayConfiguration, LayoutDirection, NameNormalizer, SymbolReplacement},
     formatter::TermDisplayContext,
 };
 
@@ -46,14 +46,27 @@ impl ConfigurationProvider {
 pub struct Configuration {
     pub display: DisplayConfiguration,
     pub term_display: TermDisplayContextFiles,
+    pub auto_disable_equalities: EqualityNodeLimit,
 }
 impl Configuration {
     pub const fn default_display() -> DisplayConfiguration {
         DisplayConfiguration {
             display_term_ids: false,
             display_quantifier_name: false,
+            prefer_qid: false,
+            derived_quant_labels: false,
+            quant_name_normalizer: NameNormalizer {
+                lowercase: false,
+                strip_id: false,
+            },
+            de_bruijn_vars: false,
+            display_line_numbers: false,
+            layout_direction: LayoutDirection::TopToBottom,
+            inline_equalities: false,
             replace_symbols: SymbolReplacement::Code,
             html: true,
+            highlight_terms: Vec::new(),
+            max_eq_steps: None,
             // Set manually elsewhere
             enode_char_limit: None,
             ast_depth_limit: None,
@@ -66,6 +79,28 @@ impl Default for Configuration {
         Self {
             display: Self::default_display(),
             term_display: TermDisplayContextFiles::default(),
+            auto_disable_equalities: EqualityNodeLimit::Above100k,
+        }
+    }
+}
+
+/// Above how many equality nodes in the graph of a newly opened file all
+/// equalities are disabled by default, to keep the app responsive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum EqualityNodeLimit {
+    Never,
+    Above10k,
+    Above100k,
+    Above1M,
+}
+
+impl EqualityNodeLimit {
+    pub fn threshold(self) -> Option<usize> {
+        match self {
+            Self::Never => None,
+            Self::Above10k => Some(10_000),
+            Self::Above100k => Some(100_000),
+            Self::Above1M => Some(1_000_000),
         }
     }
 }
@@ -1,18 +1,29 @@
 use std::rc::Rc;
 
-use smt_log_parser::display_with::SymbolReplacement;
+use smt_log_parser::display_with::{LayoutDirection, SymbolReplacement};
 use wasm_bindgen::JsCast;
 use yew::{function_component, use_context, use_effect_with_deps, Callback, Event, Html};
 
-use crate::configuration::{Configuration, ConfigurationProvider, TermDisplayFlag};
+use crate::configuration::{
+    Configuration, ConfigurationProvider, EqualityNodeLimit, TermDisplayFlag,
+};
 
 macro_rules! flag_widget {
     ($cfg:ident, $default:ident, $($access:ident).+, $title:expr, $description:expr, $($from:ident => $to:literal),+$(,)?) => {
+        flag_widget!(@widget $cfg, $default, $($access).+, $title, $description, { $($from => $to,)+ }, $($from => $to),+)
+    };
+    // For flags whose values are not all listed, e.g. numbers: a value
+    // without an option of its own (such as one stored by an older version)
+    // is shown as the `$fallback` option.
+    ($cfg:ident, $default:ident, $($access:ident).+, $title:expr, $description:expr, $($from:expr => $to:literal),+ ; _ => $fallback:literal$(,)?) => {
+        flag_widget!(@widget $cfg, $default, $($access).+, $title, $description, { $(value if *value == $from => $to,)+ _ => $fallback, }, $($from => $to),+)
+    };
+    (@widget $cfg:ident, $default:ident, $($access:ident).+, $title:expr, $description:expr, { $($to_label:tt)+ }, $($from:expr => $to:literal),+) => {
         {
             let id = stringify!(cfg.$($access).+);
             let curr = &(($cfg).config.$($access).+);
             let curr_to = match curr {
-                $($from => $to,)+
+                $($to_label)+
             };
             let effect = move |curr_to| {
                 let element = gloo::utils::document().get_element_by_id(id);
@@ -25,7 +36,7 @@ macro_rules! flag_widget {
             let deps = curr_to;
             let default = $default.$($access).+;
             let default_to = match &default {
-                $($from => $to,)+
+                $($to_label)+
             };
             let cfg_update = $cfg.update.clone();
             let onchange = Callback::from(move |e: Event| {
@@ -76,6 +87,103 @@ pub fn Flags(_props: &()) -> Html {
         false => "Disabled"
     );
     use_effect_with_deps(move |deps| effect(deps), deps);
+    let (derived_quant_labels, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.derived_quant_labels,
+        "Derived quantifier names",
+        "Name quantifiers without a name in the SMT file (e.g. `k!12`) after the function symbols in their trigger instead.",
+        true => "Enabled",
+        false => "Disabled"
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    let (quant_name_lowercase, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.quant_name_normalizer.lowercase,
+        "Case-insensitive quantifier names",
+        "Ignore case when filtering for a quantifier by name.",
+        true => "Enabled",
+        false => "Disabled"
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    let (quant_name_strip_id, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.quant_name_normalizer.strip_id,
+        "Ignore quantifier ids",
+        "Ignore a trailing `!id` (e.g. `foo!12`) when filtering for a quantifier by name, so that all quantifiers generated from the same one match.",
+        true => "Enabled",
+        false => "Disabled"
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    let (de_bruijn_vars, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.de_bruijn_vars,
+        "Nested variable names",
+        "Resolve bound variables against the innermost quantifier first, so that variables of nested quantifiers are named after the quantifier which binds them.",
+        true => "Enabled",
+        false => "Disabled"
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    let (display_line_numbers, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.display_line_numbers,
+        "Line numbers in graph",
+        "Label instantiation and enode nodes in the graph with the line of the log file which created them. Applies the next time the graph is rendered.",
+        true => "Enabled",
+        false => "Disabled"
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    use LayoutDirection::*;
+    let (layout_direction, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.layout_direction,
+        "Graph layout direction",
+        "The direction in which the graph flows from parents to children, left-to-right can be easier to read for wide but shallow graphs. Applies the next time the graph is rendered.",
+        TopToBottom => "Top to bottom",
+        LeftToRight => "Left to right",
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    let (inline_equalities, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.inline_equalities,
+        "Equalities on edges",
+        "Hide all equality nodes in the graph and instead label each edge with the equality it relies on. Applies the next time the graph is rendered.",
+        true => "Enabled",
+        false => "Disabled"
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    let (max_eq_steps, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        display.max_eq_steps,
+        "Transitive equality steps",
+        "How many of the intermediate terms of a transitive equality to show, otherwise only its two ends and the number of steps between them are shown.",
+        Option::None => "Ends only",
+        Some(5) => "Up to 5",
+        Some(20) => "Up to 20",
+        Some(usize::MAX) => "All";
+        _ => "Up to 20",
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
+    use EqualityNodeLimit::*;
+    let (auto_disable_equalities, effect, deps) = flag_widget!(
+        cfg,
+        default,
+        auto_disable_equalities,
+        "Disable equalities in large graphs",
+        "When opening a file whose graph has more equality nodes than this, disable all equalities from the start to keep the UI responsive. They can be enabled again under \"Global Operations\". Applies to files opened afterwards.",
+        Never => "Never",
+        Above10k => "Above 10k",
+        Above100k => "Above 100k",
+        Above1M => "Above 1M",
+    );
+    use_effect_with_deps(move |deps| effect(deps), deps);
     use SymbolReplacement::*;
     let (replace_symbols, effect, deps) = flag_widget!(
         cfg,
@@ -94,6 +202,15 @@ pub fn Flags(_props: &()) -> Html {
             <h1>{"Configuration flags"}</h1>
             <button onclick={reset}>{"Reset configuration"}</button>
             {display_term_ids}
+            {derived_quant_labels}
+            {quant_name_lowercase}
+            {quant_name_strip_id}
+            {de_bruijn_vars}
+            {display_line_numbers}
+            {layout_direction}
+            {inline_equalities}
+            {max_eq_steps}
+            {auto_disable_equalities}
             {replace_symbols}
             <TermDisplayFlag cfg={cfg.clone()} />
         </div></div>
@@ -2,7 +2,7 @@ use std::{cell::RefCell, rc::Rc};
 
 use gloo::file::File;
 use smt_log_parser::{
-    parsers::{AsyncBufferRead, ParseState},
+    parsers::{AsyncBufferRead, AsyncParser, ParseState},
     LogParser, Z3Parser,
 };
 use wasm_bindgen::JsCast;
@@ -114,55 +114,10 @@ impl FileDataComponent {
             Ok(stream) => {
                 let link = link.clone();
                 link.send_message(Msg::LoadingState(LoadingState::StartParsing));
-                let mut parser = Z3Parser::from_async(stream.buffer());
+                let parser = Z3Parser::from_async(stream.buffer());
                 wasm_bindgen_futures::spawn_local(async move {
                     log::info!("Parsing \"{file_name}\"");
-                    let finished = loop {
-                        let mut lines_to_read = 100_000;
-                        let finished = parser
-                            .process_until(|_, state| {
-                                lines_to_read -= 1;
-                                let pause = lines_to_read == 0;
-                                (pause
-                                    || *cancel.borrow()
-                                    || state.bytes_read >= 1024 * 1024 * 1024)
-                                    .then_some(pause)
-                            })
-                            .await;
-                        let ParseState::Paused(true, state) = finished else {
-                            break finished;
-                        };
-                        let parsing = ParseProgress::new(state, file_size);
-                        link.send_message(Msg::LoadingState(LoadingState::Parsing(
-                            parsing,
-                            cancel_cb.clone(),
-                        )));
-                        gloo::timers::future::TimeoutFuture::new(0).await;
-                    };
-                    let cancel = *cancel.borrow();
-                    match finished {
-                        ParseState::Paused(..) if !cancel => {
-                            let message = OmnibarMessage {
-                                message: "Stopped parsing at 1GB".to_string(),
-                                is_error: false,
-                            };
-                            link.send_message(Msg::ShowMessage(message, 8000));
-                        }
-                        ParseState::Error(err) => {
-                            link.send_message(Msg::FailedOpening(err.to_string()));
-                            return;
-                        }
-                        _ => (),
-                    }
-                    link.send_message(Msg::LoadingState(LoadingState::DoneParsing(
-                        finished.is_timeout(),
-                        cancel,
-                    )));
-                    link.send_message(Msg::LoadedFile(
-                        Box::new(parser.take_parser()),
-                        finished,
-                        cancel,
-                    ))
+                    Self::parse_async(parser, file_size, &link, cancel, cancel_cb).await
                 });
             }
             Err((_err, _stream)) => {
@@ -234,4 +189,146 @@ impl FileDataComponent {
         };
         changed
     }
+
+    /// Parses the trace `file` as a baseline and adds a
+    /// [`Filter::OnlyNewVs`] with the keys of its instantiations. The
+    /// baseline is not parsed as a summary since the keys of a summary
+    /// parser cannot tell bound terms apart, see [`InstKey::bound_terms`].
+    ///
+    /// [`Filter::OnlyNewVs`]: crate::results::filters::Filter::OnlyNewVs
+    /// [`InstKey::bound_terms`]: smt_log_parser::items::InstKey::bound_terms
+    pub fn load_baseline_file(&mut self, file: File, link: &Scope<FileDataComponent>) {
+        let file_name = file.name();
+        log::info!("Selected baseline \"{file_name}\"");
+        let link = link.clone();
+        let reader = gloo::file::callbacks::read_as_bytes(&file, move |res| {
+            let keys = res
+                .map_err(|err| err.to_string())
+                .and_then(|res| String::from_utf8(res).map_err(|err| err.to_string()))
+                .and_then(|text| {
+                    log::info!("Parsing baseline \"{file_name}\"");
+                    let parser = Z3Parser::from_str(&text).process_all();
+                    parser.map(|p| p.inst_keys()).map_err(|err| err.to_string())
+                });
+            match keys {
+                Ok(keys) => {
+                    let mut keys: Vec<_> = keys.into_iter().collect();
+                    keys.sort_unstable();
+                    link.send_message(Msg::LoadedBaseline(keys));
+                }
+                Err(err) => {
+                    let message = OmnibarMessage {
+                        message: format!("Failed to open baseline \"{file_name}\": {err}"),
+                        is_error: true,
+                    };
+                    link.send_message(Msg::ShowMessage(message, 10000));
+                }
+            }
+        });
+        self.baseline_reader = Some(reader);
+    }
+
+    /// Fetches the trace at `url` and parses it as it is being downloaded.
+    #[cfg(target_arch = "wasm32")]
+    pub fn load_url(&mut self, url: String, link: &Scope<FileDataComponent>) -> bool {
+        let changed = self.file.is_some();
+        drop(self.file.take());
+        drop(self.reader.take());
+
+        log::info!("Fetching \"{url}\"");
+        *self.cancel.borrow_mut() = false;
+        let cancel = self.cancel.clone();
+        let cancel_cb = Callback::from(move |_| {
+            *cancel.borrow_mut() = true;
+        });
+        let cancel = self.cancel.clone();
+        let link = link.clone();
+        link.send_message(Msg::LoadingState(LoadingState::StartParsing));
+        wasm_bindgen_futures::spawn_local(async move {
+            let (size, parser) = match smt_log_parser::parsers::fetch_and_parse(&url).await {
+                Ok(fetched) => fetched,
+                Err(err) => {
+                    let message = match err {
+                        smt_log_parser::Error::FetchStatus(status, text) => {
+                            format!("Failed to fetch \"{url}\": {status} {text}")
+                        }
+                        smt_log_parser::Error::FetchFailed(err) => format!(
+                            "Failed to fetch \"{url}\", the server may not allow cross-origin requests: {err}"
+                        ),
+                        err => format!("Failed to fetch \"{url}\": {err:?}"),
+                    };
+                    link.send_message(Msg::FailedOpening(message));
+                    return;
+                }
+            };
+            let path = url.split(['?', '#']).next().unwrap_or_default();
+            let name = path.rsplit('/').find(|s| !s.is_empty()).unwrap_or(path);
+            let name = name.to_string();
+            link.get_state().unwrap().update_file_info(move |info| {
+                *info = Some(FileInfo {
+                    name,
+                    size: size.unwrap_or_default(),
+                });
+                true
+            });
+            log::info!("Parsing \"{url}\"");
+            // Without a known size, progress stays at zero until parsing is done.
+            let file_size = size.unwrap_or(u64::MAX);
+            Self::parse_async(parser, file_size, &link, cancel, cancel_cb).await
+        });
+        changed
+    }
+
+    async fn parse_async(
+        mut parser: AsyncParser<'static, Z3Parser>,
+        file_size: u64,
+        link: &Scope<FileDataComponent>,
+        cancel: Rc<RefCell<bool>>,
+        cancel_cb: Callback<()>,
+    ) {
+        let finished = loop {
+            let mut lines_to_read = 100_000;
+            let finished = parser
+                .process_until(|_, state| {
+                    lines_to_read -= 1;
+                    let pause = lines_to_read == 0;
+                    (pause || *cancel.borrow() || state.bytes_read >= 1024 * 1024 * 1024)
+                        .then_some(pause)
+                })
+                .await;
+            let ParseState::Paused(true, state) = finished else {
+                break finished;
+            };
+            let parsing = ParseProgress::new(state, file_size);
+            link.send_message(Msg::LoadingState(LoadingState::Parsing(
+                parsing,
+                cancel_cb.clone(),
+            )));
+            gloo::timers::future::TimeoutFuture::new(0).await;
+        };
+        let cancel = *cancel.borrow();
+        match finished {
+            ParseState::Paused(..) if !cancel => {
+                let message = OmnibarMessage {
+                    message: "Stopped parsing at 1GB".to_string(),
+                    is_error: false,
+                };
+                link.send_message(Msg::ShowMessage(message, 8000));
+            }
+            ParseState::Error(err) => {
+                link.send_message(Msg::FailedOpening(err.to_string()));
+                return;
+            }
+            _ => (),
+        }
+        link.send_message(Msg::LoadingState(LoadingState::DoneParsing(
+            finished.is_timeout(),
+            cancel,
+        )));
+        link.send_message(Msg::LoadedFile(
+            Box::new(parser.take_parser()),
+            finished,
+            cancel,
+        ))
+    }
 }
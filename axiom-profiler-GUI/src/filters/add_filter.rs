@@ -40,10 +40,22 @@ pub fn AddFilterSidebar(props: &AddFilterSidebarProps) -> Html {
             vec![Filter::MaxNodeIdx(1000)],
             vec![Filter::MinNodeIdx(1000)],
             vec![Filter::IgnoreTheorySolving],
+            vec![Filter::OnlyCyclic],
+            vec![Filter::KCore(2)],
+            vec![Filter::FirstPerQuantifier],
+            vec![Filter::OnlyConflictRelevant],
             vec![Filter::MaxInsts(DEFAULT_NODE_COUNT)],
+            vec![Filter::CostPercentile(90, 99)],
             vec![Filter::MaxBranching(DEFAULT_NODE_COUNT)],
+            vec![Filter::MaxSubtreeHeight(DEFAULT_NODE_COUNT as u32)],
+            vec![Filter::TopCentrality(DEFAULT_NODE_COUNT)],
             vec![Filter::MaxDepth(6)],
+            vec![Filter::MinDecisionLevel(1)],
+            vec![Filter::HideLeaves(1)],
+            vec![Filter::ContractChains],
+            vec![Filter::DirectlyConnectedOnly],
             vec![Filter::ShowNamedQuantifier("name".to_string())],
+            vec![Filter::ContainsSymbol("symbol".to_string())],
             mls,
             mls_all,
         ]
@@ -65,6 +77,9 @@ pub fn AddFilterSidebar(props: &AddFilterSidebarProps) -> Html {
             });
             (*n, i, q)
         });
+        let mut quants: Vec<_> = nodes.clone().filter_map(|(_, _, q)| q).collect();
+        quants.sort_unstable();
+        quants.dedup();
         vec![
             nodes
                 .clone()
@@ -134,10 +149,47 @@ pub fn AddFilterSidebar(props: &AddFilterSidebarProps) -> Html {
                 .filter(|(_, i, _)| i.is_some())
                 .map(|(_, _, q)| Filter::IgnoreAllButQuantifier(q))
                 .collect(),
+            if quants.len() > 1 {
+                vec![Filter::IgnoreQuantifiers(quants.clone())]
+            } else {
+                Vec::new()
+            },
+            if quants.len() > 1 {
+                vec![Filter::OnlyQuantifiers(quants)]
+            } else {
+                Vec::new()
+            },
             nodes
                 .clone()
                 .map(|(n, _, _)| Filter::ShowLongestPath(n))
                 .collect(),
+            nodes
+                .clone()
+                .map(|(n, _, _)| Filter::ShowShortestPath(n))
+                .collect(),
+            nodes
+                .clone()
+                .map(|(n, _, _)| Filter::ShowDepthStaircase(n))
+                .collect(),
+            nodes
+                .clone()
+                .map(|(n, _, _)| Filter::ShowNearest(n, 50))
+                .collect(),
+            nodes
+                .clone()
+                .map(|(n, _, _)| Filter::FocusNearest(n, 50))
+                .collect(),
+            nodes.clone().map(|(n, _, _)| Filter::HideNode(n)).collect(),
+            nodes
+                .clone()
+                .filter(|&(n, _, _)| graph.raw[n].hidden())
+                .map(|(n, _, _)| Filter::ShowNode(n))
+                .collect(),
+            if props.nodes.len() > 1 {
+                vec![Filter::ConnectSelected(props.nodes.clone())]
+            } else {
+                Vec::new()
+            },
         ]
     };
     let filters = filters.into_iter().map(|f| {
@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use gloo::timers::callback::Timeout;
 use material_yew::icon::MatIcon;
-use smt_log_parser::items::QuantIdx;
+use smt_log_parser::{analysis::RawNodeIndex, items::QuantIdx};
 use web_sys::{Element, HtmlElement, HtmlInputElement};
 use yew::{
     function_component, html, use_context, Callback, Children, Component, Context, Html, NodeRef,
@@ -377,6 +377,12 @@ impl Filter {
         !matches!(
             self,
             Filter::IgnoreTheorySolving
+                | Filter::OnlyCyclic
+                | Filter::FirstPerQuantifier
+                | Filter::OnlyConflictRelevant
+                | Filter::ContractChains
+                | Filter::DirectlyConnectedOnly
+                | Filter::OnlyNewVs(..)
                 | Filter::ShowMatchingLoopSubgraph
                 | Filter::IgnoreQuantifier(None)
                 | Filter::IgnoreAllButQuantifier(None)
@@ -384,6 +390,11 @@ impl Filter {
                 | Filter::VisitSourceTree(..)
                 | Filter::VisitSubTreeWithRoot(..)
                 | Filter::ShowLongestPath(..)
+                | Filter::ShowShortestPath(..)
+                | Filter::ShowDepthStaircase(..)
+                | Filter::ConnectSelected(..)
+                | Filter::IgnoreQuantifiers(..)
+                | Filter::OnlyQuantifiers(..)
         )
     }
     pub fn update(&self, new_data: Vec<usize>, new_strings: Vec<String>) -> Filter {
@@ -391,26 +402,55 @@ impl Filter {
             Filter::MaxNodeIdx(_) => Filter::MaxNodeIdx(new_data[0]),
             Filter::MinNodeIdx(_) => Filter::MinNodeIdx(new_data[0]),
             Filter::IgnoreTheorySolving => Filter::IgnoreTheorySolving,
+            Filter::OnlyCyclic => Filter::OnlyCyclic,
+            Filter::KCore(_) => Filter::KCore(new_data[0]),
+            Filter::FirstPerQuantifier => Filter::FirstPerQuantifier,
+            Filter::OnlyConflictRelevant => Filter::OnlyConflictRelevant,
             Filter::IgnoreQuantifier(_) => {
                 Filter::IgnoreQuantifier(Some(QuantIdx::from(new_data[0])))
             }
             Filter::IgnoreAllButQuantifier(_) => {
                 Filter::IgnoreAllButQuantifier(Some(QuantIdx::from(new_data[0])))
             }
+            Filter::IgnoreQuantifiers(old) => Filter::IgnoreQuantifiers(old.clone()),
+            Filter::OnlyQuantifiers(old) => Filter::OnlyQuantifiers(old.clone()),
             Filter::MaxInsts(_) => Filter::MaxInsts(new_data[0]),
+            Filter::CostPercentile(..) => Filter::CostPercentile(
+                u8::try_from(new_data[0]).unwrap_or(u8::MAX),
+                u8::try_from(new_data[1]).unwrap_or(u8::MAX),
+            ),
             Filter::MaxBranching(_) => Filter::MaxBranching(new_data[0]),
+            Filter::MaxSubtreeHeight(_) => {
+                Filter::MaxSubtreeHeight(u32::try_from(new_data[0]).unwrap_or(u32::MAX))
+            }
+            Filter::TopCentrality(_) => Filter::TopCentrality(new_data[0]),
             Filter::ShowNeighbours(old, dir) => Filter::ShowNeighbours(*old, *dir),
             Filter::VisitSourceTree(old, retain) => Filter::VisitSourceTree(*old, *retain),
             Filter::VisitSubTreeWithRoot(old, retain) => {
                 Filter::VisitSubTreeWithRoot(*old, *retain)
             }
             Filter::MaxDepth(_) => Filter::MaxDepth(new_data[0]),
+            Filter::MinDecisionLevel(_) => {
+                Filter::MinDecisionLevel(u32::try_from(new_data[0]).unwrap_or(u32::MAX))
+            }
             Filter::ShowLongestPath(old) => Filter::ShowLongestPath(*old),
+            Filter::ShowShortestPath(old) => Filter::ShowShortestPath(*old),
+            Filter::ShowDepthStaircase(old) => Filter::ShowDepthStaircase(*old),
+            Filter::ShowNearest(old, _) => Filter::ShowNearest(*old, new_data[0]),
+            Filter::FocusNearest(old, _) => Filter::FocusNearest(*old, new_data[0]),
+            Filter::HideNode(_) => Filter::HideNode(RawNodeIndex::from(new_data[0])),
+            Filter::ShowNode(_) => Filter::ShowNode(RawNodeIndex::from(new_data[0])),
             Filter::ShowNamedQuantifier(_) => Filter::ShowNamedQuantifier(new_strings[0].clone()),
+            Filter::ContainsSymbol(_) => Filter::ContainsSymbol(new_strings[0].clone()),
             Filter::SelectNthMatchingLoop(_) => {
                 Filter::SelectNthMatchingLoop(new_data[0].max(1) - 1)
             }
             Filter::ShowMatchingLoopSubgraph => Filter::ShowMatchingLoopSubgraph,
+            Filter::ConnectSelected(old) => Filter::ConnectSelected(old.clone()),
+            Filter::HideLeaves(_) => Filter::HideLeaves(new_data[0]),
+            Filter::ContractChains => Filter::ContractChains,
+            Filter::DirectlyConnectedOnly => Filter::DirectlyConnectedOnly,
+            Filter::OnlyNewVs(old) => Filter::OnlyNewVs(old.clone()),
         }
     }
 }
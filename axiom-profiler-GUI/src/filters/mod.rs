@@ -1,11 +1,13 @@
 mod add_filter;
 mod manage_filter;
+mod presets;
 
 use std::fmt::Display;
 
 use material_yew::icon::MatIcon;
 use petgraph::Direction;
-use smt_log_parser::analysis::{raw::NodeKind, RawNodeIndex};
+use smt_log_parser::analysis::{raw::NodeKind, visible::EdgeVisibility, RawNodeIndex};
+use smt_log_parser::items::QuantIdx;
 use smt_log_parser::parsers::ParseState;
 use yew::{html, Callback, Component, Context, Html, MouseEvent, NodeRef, Properties};
 
@@ -25,6 +27,7 @@ use crate::{
 };
 
 use self::manage_filter::DragState;
+use self::presets::FilterPreset;
 use material_yew::WeakComponentLink;
 
 #[derive(Properties, PartialEq)]
@@ -46,7 +49,13 @@ pub enum Msg {
     EndEdit(usize, Filter),
     AddFilter(bool, Filter),
     ToggleDisabler(usize),
+    /// The graph has this many equality nodes, more than the configured
+    /// limit, and so all equalities were disabled when it was constructed.
+    AutoDisabledEqualities(usize),
+    CycleEdgeVisibility,
     ToggleMlViewerMode,
+    SavePreset,
+    ApplyPreset(usize),
 }
 
 pub struct FiltersState {
@@ -54,12 +63,17 @@ pub struct FiltersState {
     delete_node: NodeRef,
     will_delete: bool,
     disabler_chain: Vec<(Disabler, bool)>,
+    /// Set while equalities are disabled due to
+    /// [`Msg::AutoDisabledEqualities`], to tell the user why.
+    auto_disabled_equalities: Option<usize>,
+    edge_visibility: EdgeVisibility,
     filter_chain: Vec<Filter>,
     applied_filter_chain: Vec<Filter>,
     prev_filter_chain: Vec<Filter>,
     selected_filter: Option<usize>,
     edit_filter: Option<usize>,
     global_section: NodeRef,
+    presets: Vec<FilterPreset>,
 }
 
 impl FiltersState {
@@ -110,6 +124,8 @@ impl Component for FiltersState {
         let applied_filter_chain = filter_chain.clone();
         let mut self_ = Self {
             disabler_chain,
+            auto_disabled_equalities: None,
+            edge_visibility: EdgeVisibility::default(),
             filter_chain,
             prev_filter_chain,
             applied_filter_chain,
@@ -119,6 +135,7 @@ impl Component for FiltersState {
             selected_filter: None,
             edit_filter: None,
             global_section: NodeRef::default(),
+            presets: presets::load_presets(),
         };
         self_.reset_disabled(&ctx.props().file);
         self_
@@ -215,8 +232,19 @@ impl Component for FiltersState {
                     }
                 }
                 self.prev_filter_chain.clone_from(&self.filter_chain);
-                self.edit_filter = edit.then_some(self.filter_chain.len());
-                self.filter_chain.push(filter);
+                // Fold the new filter into the last one if that makes no
+                // difference (e.g. hiding theory solving twice).
+                let merged = self
+                    .filter_chain
+                    .last()
+                    .and_then(|last| last.merge(&filter));
+                match merged.filter(|_| !edit) {
+                    Some(merged) => *self.filter_chain.last_mut().unwrap() = merged,
+                    None => {
+                        self.edit_filter = edit.then_some(self.filter_chain.len());
+                        self.filter_chain.push(filter);
+                    }
+                }
                 if !edit {
                     self.send_updates(&ctx.props().file, true);
                 }
@@ -225,7 +253,30 @@ impl Component for FiltersState {
             Msg::ToggleDisabler(idx) => {
                 self.disabler_chain[idx].1 = !self.disabler_chain[idx].1;
                 self.reset_disabled(&ctx.props().file);
-                false
+                self.auto_disabled_equalities.take().is_some()
+            }
+            Msg::AutoDisabledEqualities(equality_nodes) => {
+                // The graph has already applied the disabler.
+                for (disabler, enabled) in &mut self.disabler_chain {
+                    if *disabler == Disabler::AllEqualities {
+                        *enabled = true;
+                    }
+                }
+                self.auto_disabled_equalities = Some(equality_nodes);
+                true
+            }
+            Msg::CycleEdgeVisibility => {
+                self.edge_visibility = match self.edge_visibility {
+                    EdgeVisibility::Both => EdgeVisibility::DirectOnly,
+                    EdgeVisibility::DirectOnly => EdgeVisibility::IndirectOnly,
+                    EdgeVisibility::IndirectOnly => EdgeVisibility::Both,
+                };
+                let msgs = [
+                    SVGMsg::SetEdgeVisibility(self.edge_visibility),
+                    SVGMsg::RenderGraph,
+                ];
+                ctx.props().file.send_updates(msgs.into_iter());
+                true
             }
             Msg::ToggleMlViewerMode => {
                 let state = ctx.link().get_state().unwrap();
@@ -236,6 +287,45 @@ impl Component for FiltersState {
                 state.set_ml_viewer_mode(!state.state.ml_viewer_mode);
                 true
             }
+            Msg::SavePreset => {
+                let Some(name) = gloo::dialogs::prompt("Name of the preset", None) else {
+                    return false;
+                };
+                let preset = FilterPreset {
+                    name,
+                    filters: self.filter_chain.clone(),
+                };
+                match self.presets.iter_mut().find(|p| p.name == preset.name) {
+                    Some(existing) => *existing = preset,
+                    None => self.presets.push(preset),
+                }
+                presets::save_presets(&self.presets);
+                true
+            }
+            Msg::ApplyPreset(idx) => {
+                let state = ctx.link().get_state().unwrap();
+                let parser = state.state.parser.as_ref().unwrap();
+                let node_count = parser
+                    .graph
+                    .as_ref()
+                    .map(|g| g.borrow().raw.graph.node_count())
+                    .unwrap_or_default();
+                let quant_count = parser.parser.borrow().quantifiers().count();
+                let preset = &self.presets[idx];
+                let (valid, invalid): (Vec<_>, Vec<_>) = preset
+                    .filters
+                    .iter()
+                    .cloned()
+                    .partition(|f| f.is_valid_for(node_count, quant_count));
+                if !invalid.is_empty() {
+                    log::warn!(
+                        "Skipping operations of preset \"{}\" not valid for this file: {invalid:?}",
+                        preset.name
+                    );
+                }
+                self.filter_chain = valid;
+                self.send_updates(&ctx.props().file, true)
+            }
         }
     }
 
@@ -298,6 +388,21 @@ impl Component for FiltersState {
             }
         });
         let new_filter = ctx.link().callback(|f| Msg::AddFilter(true, f));
+        let save_preset = ctx.link().callback(|e: MouseEvent| {
+            e.prevent_default();
+            Msg::SavePreset
+        });
+        let presets = self.presets.iter().enumerate().map(|(idx, preset)| {
+            let apply = ctx.link().callback(move |e: MouseEvent| {
+                e.prevent_default();
+                Msg::ApplyPreset(idx)
+            });
+            html! {
+                <li><a draggable="false" href="#" onclick={apply}>
+                    <div class="material-icons"><MatIcon>{"bookmark"}</MatIcon></div>{format!("Apply preset \"{}\"", preset.name)}
+                </a></li>
+            }
+        });
 
         // Selected nodes
         let selected_nodes = !ctx.props().file.selected_nodes.is_empty();
@@ -342,7 +447,7 @@ impl Component for FiltersState {
         });
         // Disablers
         let toggle = ctx.link().callback(Msg::ToggleDisabler);
-        let selected: Vec<_> = DEFAULT_DISABLER_CHAIN.iter().map(|(_, b)| *b).collect();
+        let selected: Vec<_> = self.disabler_chain.iter().map(|(_, b)| *b).collect();
         let disablers = self.disabler_chain.iter().map(|(d, b)| {
             let onclick = Callback::from(move |e: MouseEvent| e.prevent_default());
             let action = if *b { "Enable " } else { "Disable " };
@@ -351,6 +456,19 @@ impl Component for FiltersState {
                 <div class="material-icons"><MatIcon>{icon}</MatIcon></div>{action}{d.description()}
             </a> }
         });
+        let auto_disabled = self.auto_disabled_equalities.map(|equality_nodes| {
+            let details = format!("Disabled all equalities since there are {equality_nodes}");
+            html! { <li><a draggable="false" class="trace-file-name">{details}</a></li> }
+        });
+        let cycle_edges = ctx.link().callback(|e: MouseEvent| {
+            e.prevent_default();
+            Msg::CycleEdgeVisibility
+        });
+        let edge_visibility = match self.edge_visibility {
+            EdgeVisibility::Both => "Showing direct and indirect edges",
+            EdgeVisibility::DirectOnly => "Showing only direct edges",
+            EdgeVisibility::IndirectOnly => "Showing only indirect edges",
+        };
         let normal_mode = if ctx.link().get_state().unwrap().state.ml_viewer_mode {
             html! {}
         } else {
@@ -359,6 +477,8 @@ impl Component for FiltersState {
                 <AddFilterSidebar new_filter={new_filter} found_mls={found_mls} nodes={Vec::new()} general_filters={true}/>
                 <li><a draggable="false" href="#" onclick={reset}><div class="material-icons"><MatIcon>{"restore"}</MatIcon></div>{"Reset operations"}</a></li>
                 {undo}
+                <li><a draggable="false" href="#" onclick={save_preset}><div class="material-icons"><MatIcon>{"bookmark_add"}</MatIcon></div>{"Save operations as preset"}</a></li>
+                {for presets}
                 </>
             }
         };
@@ -378,9 +498,11 @@ impl Component for FiltersState {
                 </DraggableList>
             </ul></SidebarSectionHeader>
             <SidebarSectionHeader header_text={"Global Operations"} collapsed_text={"Enable/Disable nodes by category"} section={self.global_section.clone()}><ul>
+            {auto_disabled}
             <ToggleList {toggle} {selected}>
                 {for disablers}
             </ToggleList>
+            <li><a draggable="false" href="#" onclick={cycle_edges}><div class="material-icons"><MatIcon>{"timeline"}</MatIcon></div>{edge_visibility}</a></li>
             </ul></SidebarSectionHeader>
         </>
         }
@@ -410,18 +532,40 @@ impl Filter {
             Filter::MaxNodeIdx(_) => "tag",
             Filter::MinNodeIdx(_) => "tag",
             Filter::IgnoreTheorySolving => "calculate",
+            Filter::OnlyCyclic => "cycle",
+            Filter::KCore(_) => "join_inner",
+            Filter::FirstPerQuantifier => "filter_1",
+            Filter::OnlyConflictRelevant => "crisis_alert",
             Filter::IgnoreQuantifier(_) => "do_not_disturb",
             Filter::IgnoreAllButQuantifier(_) => "disabled_visible",
+            Filter::IgnoreQuantifiers(_) => "do_not_disturb",
+            Filter::OnlyQuantifiers(_) => "disabled_visible",
             Filter::MaxInsts(_) => "attach_money",
+            Filter::CostPercentile(..) => "percent",
             Filter::MaxBranching(_) => "panorama_horizontal",
+            Filter::MaxSubtreeHeight(_) => "height",
+            Filter::TopCentrality(_) => "share",
             Filter::ShowNeighbours(_, _) => "supervisor_account",
             Filter::VisitSourceTree(_, _) => "arrow_upward",
             Filter::VisitSubTreeWithRoot(_, _) => "arrow_downward",
             Filter::MaxDepth(_) => "link",
+            Filter::MinDecisionLevel(_) => "account_tree",
             Filter::ShowLongestPath(_) => "route",
+            Filter::ShowShortestPath(_) => "straight",
+            Filter::ShowDepthStaircase(_) => "stairs",
+            Filter::ShowNearest(..) => "explore",
+            Filter::FocusNearest(..) => "center_focus_strong",
+            Filter::HideNode(_) => "visibility_off",
+            Filter::ShowNode(_) => "visibility",
             Filter::ShowNamedQuantifier(_) => "fingerprint",
+            Filter::ContainsSymbol(_) => "function",
             Filter::SelectNthMatchingLoop(_) => "repeat_one",
             Filter::ShowMatchingLoopSubgraph => "repeat",
+            Filter::ConnectSelected(_) => "hub",
+            Filter::HideLeaves(_) => "content_cut",
+            Filter::ContractChains => "linear_scale",
+            Filter::DirectlyConnectedOnly => "link",
+            Filter::OnlyNewVs(_) => "fiber_new",
         }
     }
     pub fn short_text(&self, d: impl Fn(RawNodeIndex) -> NodeKind) -> String {
@@ -429,6 +573,10 @@ impl Filter {
             Self::MaxNodeIdx(node_idx) => format!("Hide all ≥ |{node_idx}|"),
             Self::MinNodeIdx(node_idx) => format!("Hide all < |{node_idx}|"),
             Self::IgnoreTheorySolving => "Hide theory solving".to_string(),
+            Self::OnlyCyclic => "Only cyclic triggers".to_string(),
+            Self::KCore(k) => format!("Show only |{k}|-core"),
+            Self::FirstPerQuantifier => "Show first per quant".to_string(),
+            Self::OnlyConflictRelevant => "Only conflict relevant".to_string(),
             Self::IgnoreQuantifier(None) => "Hide no quant".to_string(),
             Self::IgnoreQuantifier(Some(qidx)) => {
                 format!("Hide quant |{qidx}|")
@@ -437,10 +585,17 @@ impl Filter {
             Self::IgnoreAllButQuantifier(Some(qidx)) => {
                 format!("Hide all but quant ${qidx:?}$")
             }
+            Self::IgnoreQuantifiers(qidxs) => format!("Hide {} quants", qidxs.len()),
+            Self::OnlyQuantifiers(qidxs) => format!("Hide all but {} quants", qidxs.len()),
             Self::MaxInsts(max) => format!("Hide all but |{max}| expensive"),
+            Self::CostPercentile(lo, hi) => format!("Show cost |{lo}|-|{hi}|%"),
             Self::MaxBranching(max) => {
                 format!("Hide all but |{max}| high degree")
             }
+            Self::MaxSubtreeHeight(max) => {
+                format!("Hide all but |{max}| tallest subtrees")
+            }
+            Self::TopCentrality(max) => format!("Hide all but |{max}| most central"),
             &Self::VisitSubTreeWithRoot(nidx, retain) => match retain {
                 true => format!("Show descendants of ${}$", d(nidx)),
                 false => format!("Hide descendants of ${}$", d(nidx)),
@@ -454,12 +609,24 @@ impl Filter {
                 Direction::Outgoing => format!("Show children of ${}$", d(nidx)),
             },
             Self::MaxDepth(depth) => format!("Hide all > depth |{depth}|"),
+            Self::MinDecisionLevel(level) => format!("Hide all < level |{level}|"),
             &Self::ShowLongestPath(node) => {
                 format!("Show longest path w/ ${}$", d(node))
             }
+            &Self::ShowShortestPath(node) => {
+                format!("Show shortest path w/ ${}$", d(node))
+            }
+            &Self::ShowDepthStaircase(node) => {
+                format!("Show depth staircase from ${}$", d(node))
+            }
+            &Self::ShowNearest(node, n) => format!("Show |{n}| nearest to ${}$", d(node)),
+            &Self::FocusNearest(node, n) => format!("Focus |{n}| nearest to ${}$", d(node)),
+            Self::HideNode(node) => format!("Hide node |{}|", node.0.index()),
+            Self::ShowNode(node) => format!("Show node |{}|", node.0.index()),
             Self::ShowNamedQuantifier(name) => {
                 format!("Show quant \"{name}\"")
             }
+            Self::ContainsSymbol(symbol) => format!("Show containing \"{symbol}\""),
             Self::SelectNthMatchingLoop(n) => {
                 let ordinal = match n {
                     n if (n / 10) % 10 == 1 => "th",
@@ -471,6 +638,11 @@ impl Filter {
                 format!("Show only |{}{ordinal}| matching loop", n + 1)
             }
             Self::ShowMatchingLoopSubgraph => "S only likely matching loops".to_string(),
+            Self::ConnectSelected(nodes) => format!("Connect {} selected", nodes.len()),
+            Self::HideLeaves(layers) => format!("Hide |{layers}| leaf layers"),
+            Self::ContractChains => "Contract chains".to_string(),
+            Self::DirectlyConnectedOnly => "Only directly connected".to_string(),
+            Self::OnlyNewVs(baseline) => format!("Show new vs |{}| baseline", baseline.len()),
         }
     }
     pub fn long_text(&self, d: impl Fn(RawNodeIndex) -> NodeKind, applied: bool) -> String {
@@ -487,6 +659,19 @@ impl Filter {
                 format!("{hide} all nodes below {}", display(node_idx, applied))
             }
             Self::IgnoreTheorySolving => format!("{hide} all nodes related to theory solving"),
+            Self::OnlyCyclic => format!(
+                "{hide} all instantiations of triggers which do not (indirectly) cause themselves"
+            ),
+            Self::KCore(k) => format!(
+                "{hide} all instantiations outside of the largest group where each is connected to at least {} others in the group",
+                display(k, applied)
+            ),
+            Self::FirstPerQuantifier => format!(
+                "{hide} all instantiations except for the earliest one of each quantifier"
+            ),
+            Self::OnlyConflictRelevant => format!(
+                "{hide} all instantiations which did not (indirectly) lead to a conflict"
+            ),
             Self::IgnoreQuantifier(None) => {
                 format!("{hide} all nodes without an associated quantifier")
             }
@@ -502,16 +687,45 @@ impl Filter {
                     display(qidx, applied)
                 )
             }
+            Self::IgnoreQuantifiers(qidxs) => {
+                format!(
+                    "{hide} all nodes of quantifiers {}",
+                    display(quant_list(qidxs), applied)
+                )
+            }
+            Self::OnlyQuantifiers(qidxs) => {
+                format!(
+                    "{hide} all nodes not associated to quantifiers {}",
+                    display(quant_list(qidxs), applied)
+                )
+            }
             Self::MaxInsts(max) => format!(
                 "{hide} all but the {} most expensive nodes",
                 display(max, applied)
             ),
+            Self::CostPercentile(lo, hi) => format!(
+                "{hide} all nodes except those more expensive than {}% but at most as expensive as {}% of the others",
+                display(lo, applied),
+                display(hi, applied)
+            ),
             Self::MaxBranching(max) => {
                 format!(
                     "{hide} all but {} nodes with the most children",
                     display(max, applied)
                 )
             }
+            Self::MaxSubtreeHeight(max) => {
+                format!(
+                    "{hide} all but {} nodes with the longest paths below them",
+                    display(max, applied)
+                )
+            }
+            Self::TopCentrality(max) => {
+                format!(
+                    "{hide} all but {} nodes which lie on the most shortest paths between other nodes (estimated from a sample)",
+                    display(max, applied)
+                )
+            }
             &Self::VisitSubTreeWithRoot(nidx, retain) => match retain {
                 true => format!(
                     "{show} node {} and its descendants",
@@ -543,15 +757,50 @@ impl Filter {
             Self::MaxDepth(depth) => {
                 format!("{hide} all nodes above depth {}", display(depth, applied))
             }
+            Self::MinDecisionLevel(level) => {
+                format!(
+                    "{hide} all instantiations below decision level {} and nodes not produced by the rest",
+                    display(level, applied)
+                )
+            }
             &Self::ShowLongestPath(node) => {
                 format!(
                     "{show} only nodes on the longest path through node {}",
                     display(d(node), applied)
                 )
             }
+            &Self::ShowShortestPath(node) => {
+                format!(
+                    "{show} only nodes on a shortest path through node {}",
+                    display(d(node), applied)
+                )
+            }
+            &Self::ShowDepthStaircase(node) => {
+                format!(
+                    "{show} only the chain of ever deeper instantiations from node {}",
+                    display(d(node), applied)
+                )
+            }
+            &Self::ShowNearest(node, n) => format!(
+                "{show} only the {} nodes closest to node {}",
+                display(n, applied),
+                display(d(node), applied)
+            ),
+            &Self::FocusNearest(node, n) => format!(
+                "{} all but the {} nodes closest to node {}",
+                if applied { "Dimming" } else { "Dim" },
+                display(n, applied),
+                display(d(node), applied)
+            ),
+            &Self::HideNode(node) => format!("{hide} node {}", display(d(node), applied)),
+            &Self::ShowNode(node) => format!("{show} node {}", display(d(node), applied)),
             Self::ShowNamedQuantifier(name) => {
                 format!("{show} nodes of quantifier \"{}\"", display(name, applied))
             }
+            Self::ContainsSymbol(symbol) => format!(
+                "{show} only instantiations with a bound, resulting or yielded term containing the symbol \"{}\"",
+                display(symbol, applied)
+            ),
             Self::SelectNthMatchingLoop(n) => {
                 let ordinal = match n {
                     0 => return "{show} only nodes in longest matching loop".to_string(),
@@ -569,10 +818,37 @@ impl Filter {
             Self::ShowMatchingLoopSubgraph => {
                 format!("{show} only nodes in any potential matching loop")
             }
+            Self::ConnectSelected(nodes) => {
+                format!(
+                    "{show} the {} selected nodes and the paths connecting them",
+                    nodes.len()
+                )
+            }
+            Self::HideLeaves(layers) => {
+                format!(
+                    "{hide} visible nodes without visible children, repeated {} times",
+                    display(layers, applied)
+                )
+            }
+            Self::ContractChains => format!(
+                "{hide} visible nodes with exactly one visible parent and child, contracting chains into a single edge"
+            ),
+            Self::DirectlyConnectedOnly => format!(
+                "{hide} visible nodes which are only connected to other visible nodes through hidden nodes"
+            ),
+            Self::OnlyNewVs(baseline) => format!(
+                "{show} only instantiations which are not among the {} instantiations of the baseline trace",
+                display(baseline.len(), applied)
+            ),
         }
     }
 }
 
+fn quant_list(qidxs: &[QuantIdx]) -> String {
+    let qidxs: Vec<_> = qidxs.iter().map(|q| q.to_string()).collect();
+    qidxs.join(", ")
+}
+
 fn display<T: Display>(t: T, applied: bool) -> String {
     if applied {
         t.to_string()
@@ -0,0 +1,29 @@
+use gloo::storage::Storage;
+
+use crate::results::filters::Filter;
+
+const PRESETS_KEY: &str = "filter_presets";
+
+/// A named chain of graph operations, kept in local storage so that it can be
+/// re-applied to other files.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    pub filters: Vec<Filter>,
+}
+
+pub fn load_presets() -> Vec<FilterPreset> {
+    let presets = gloo::storage::LocalStorage::get::<Vec<FilterPreset>>(PRESETS_KEY);
+    match &presets {
+        Ok(_) | Err(gloo::storage::errors::StorageError::KeyNotFound(_)) => {}
+        Err(result) => log::error!("Filter presets load error: {result:?}"),
+    }
+    presets.unwrap_or_default()
+}
+
+pub fn save_presets(presets: &[FilterPreset]) {
+    let result = gloo::storage::LocalStorage::set::<&[FilterPreset]>(PRESETS_KEY, presets);
+    if let Err(result) = result {
+        log::error!("Filter presets save error: {result:?}");
+    }
+}
@@ -30,6 +30,8 @@ pub struct OmniboxProps {
     pub search: Callback<String, Option<SearchActionResult>>,
     pub pick: Callback<(String, Kind), Option<Vec<RawNodeIndex>>>,
     pub select: Callback<RawNodeIndex>,
+    /// The node created by the given line of the log, if any.
+    pub line: Callback<usize, Option<RawNodeIndex>>,
 }
 
 pub enum Msg {
@@ -209,7 +211,7 @@ impl Component for Omnibox {
                 }
                 _ => false,
             },
-            Msg::Input(_ev) => {
+            Msg::Input(ev) => {
                 self.highlighted = 0;
                 let query = ctx
                     .props()
@@ -218,6 +220,14 @@ impl Component for Omnibox {
                     .map(|r| r.value())
                     .unwrap_or_default();
 
+                // Pasting a line number of the log jumps to its node.
+                let pasted_line = (!self.command_mode && ev.input_type() == "insertFromPaste")
+                    .then(|| query.trim().parse::<usize>().ok())
+                    .flatten();
+                if let Some(node) = pasted_line.and_then(|line| ctx.props().line.emit(line)) {
+                    ctx.props().select.emit(node);
+                }
+
                 if self.command_mode {
                     self.set_picked(None);
                     self.input = Some(query).filter(|q| !q.is_empty());
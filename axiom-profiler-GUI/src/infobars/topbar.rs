@@ -23,6 +23,7 @@ pub struct TopbarProps {
     pub search: Callback<String, Option<SearchActionResult>>,
     pub pick: Callback<(String, Kind), Option<Vec<RawNodeIndex>>>,
     pub select: Callback<RawNodeIndex>,
+    pub line: Callback<usize, Option<RawNodeIndex>>,
     pub pick_nth_ml: Callback<usize>,
 }
 
@@ -69,7 +70,7 @@ pub fn Topbar(props: &TopbarProps) -> Html {
         }
     } else {
         html! {
-            <Omnibox progress={props.progress.clone()} message={props.message.clone()} omnibox={props.omnibox.clone()} search={props.search.clone()} pick={props.pick.clone()} select={props.select.clone()} />
+            <Omnibox progress={props.progress.clone()} message={props.message.clone()} omnibox={props.omnibox.clone()} search={props.search.clone()} pick={props.pick.clone()} select={props.select.clone()} line={props.line.clone()} />
         }
     };
     let topbar_class = if ml_viewer_mode { "topbar ml-mode" } else { "topbar" };
@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::ops::ControlFlow;
 use std::rc::Rc;
 use std::sync::{Mutex, OnceLock, RwLock};
 
@@ -14,6 +15,7 @@ use results::svg_result::{
     Msg as SVGMsg, QuantIdxToColourMap, RenderedGraph, RenderingState, SVGResult,
 };
 use smt_log_parser::analysis::{InstGraph, RawNodeIndex, VisibleEdgeIndex};
+use smt_log_parser::items::InstKey;
 use smt_log_parser::parsers::z3::z3parser::Z3Parser;
 use smt_log_parser::parsers::{ParseState, ReaderState};
 use wasm_bindgen::closure::Closure;
@@ -71,6 +73,8 @@ pub static PREVENT_DEFAULT_DRAG_OVER: OnceLock<Mutex<bool>> = OnceLock::new();
 pub enum Msg {
     File(Option<File>),
     LoadedFile(Box<Z3Parser>, ParseState<bool>, bool),
+    BaselineFile(Option<File>),
+    LoadedBaseline(Vec<InstKey>),
     LoadingState(LoadingState),
     RenderedGraph(RenderedGraph),
     FailedOpening(String),
@@ -181,6 +185,8 @@ pub struct FileDataComponent {
     file_select: NodeRef,
     file: Option<OpenedFileInfo>,
     reader: Option<FileReader>,
+    baseline_select: NodeRef,
+    baseline_reader: Option<FileReader>,
     pending_ops: usize,
     progress: LoadingState,
     message: Option<(Timeout, OmnibarMessage)>,
@@ -307,6 +313,8 @@ impl Component for FileDataComponent {
             file_select: NodeRef::default(),
             file: None,
             reader: None,
+            baseline_select: NodeRef::default(),
+            baseline_reader: None,
             pending_ops: 0,
             progress: LoadingState::NoFileSelected,
             message: None,
@@ -452,6 +460,31 @@ impl Component for FileDataComponent {
                 }
                 true
             }
+            Msg::BaselineFile(file) => {
+                let Some(file) = file else {
+                    return false;
+                };
+                if self.file.is_none() {
+                    let message = OmnibarMessage {
+                        message: "Open a trace before comparing it to a baseline".to_string(),
+                        is_error: true,
+                    };
+                    self.set_message(ctx.link(), message, 8000);
+                    return true;
+                }
+                self.load_baseline_file(file, ctx.link());
+                false
+            }
+            Msg::LoadedBaseline(baseline) => {
+                drop(self.baseline_reader.take());
+                if let Some(filters_state_link) = &*self.filters_state_link.borrow() {
+                    filters_state_link.send_message(crate::filters::Msg::AddFilter(
+                        false,
+                        Filter::OnlyNewVs(baseline),
+                    ));
+                }
+                false
+            }
             Msg::SelectedNodes(nodes) => {
                 let Some(file) = &mut self.file else {
                     return false;
@@ -512,10 +545,13 @@ impl Component for FileDataComponent {
                     let state = ctx.link().get_state().unwrap();
                     let parser = state.state.parser.as_ref().unwrap();
                     if let Some(g) = &parser.graph {
-                        let found_mls = Some(
-                            g.borrow_mut()
-                                .search_matching_loops(&mut parser.parser.borrow_mut()),
-                        );
+                        let found_mls = Some(g.borrow_mut().search_matching_loops_with(
+                            &mut parser.parser.borrow_mut(),
+                            |_, n, end, _| {
+                                log::info!("Found matching loop {} ending at {end:?}", n + 1);
+                                ControlFlow::Continue(())
+                            },
+                        ));
                         state.update_parser(move |p| {
                             p.as_mut().unwrap().found_mls = found_mls;
                             true
@@ -581,6 +617,14 @@ impl Component for FileDataComponent {
                 visible_ref.as_deref(),
             ))
         });
+        let line = Callback::from({
+            let parser = parser.clone();
+            move |line: usize| {
+                let parser = parser.as_ref()?;
+                let graph = parser.graph.as_ref()?.borrow();
+                graph.node_at_line(&parser.parser.borrow(), line)
+            }
+        });
         let pick = Callback::from(move |(name, kind): (String, _)| {
             let parser = parser.as_ref()?;
             let entry = parser.lookup.get_exact(&name)?.get(&kind)?;
@@ -596,6 +640,13 @@ impl Component for FileDataComponent {
             insts_info_link.send_message(graph_info::Msg::ScrollZoomSelection);
         });
         let filters_state_link = self.filters_state_link.clone();
+        let add_filter = Callback::from(move |filter: Filter| {
+            let Some(filters_state_link) = &*filters_state_link.borrow() else {
+                return;
+            };
+            filters_state_link.send_message(crate::filters::Msg::AddFilter(false, filter));
+        });
+        let filters_state_link = self.filters_state_link.clone();
         let pick_nth_ml = Callback::from({
             let _file = self.file.clone();
             move |n: usize| {
@@ -620,6 +671,18 @@ impl Component for FileDataComponent {
                     .and_then(|files| (files.len() == 1).then(|| files[0].clone())),
             )
         });
+        let baseline_select_ref = self.baseline_select.clone();
+        let on_baseline_change = ctx.link().callback(move |_| {
+            let files = baseline_select_ref
+                .cast::<HtmlInputElement>()
+                .unwrap()
+                .files();
+            Msg::BaselineFile(
+                files
+                    .map(FileList::from)
+                    .and_then(|files| (files.len() == 1).then(|| files[0].clone())),
+            )
+        });
         let sidebar_ref = sidebar.clone();
         let open_files = self.file.is_some();
         let hide_sidebar = Callback::from(move |_| {
@@ -649,7 +712,10 @@ impl Component for FileDataComponent {
             });
             let selected_nodes = ctx.link().callback(Msg::SelectedNodes);
             let selected_edges = ctx.link().callback(Msg::SelectedEdges);
-            html! {<SVGResult file={f.clone()} {progress} {selected_nodes} {selected_edges} insts_info_link={self.insts_info_link.clone()}/>}
+            let show_error = ctx.link().callback(|message| {
+                Msg::ShowMessage(OmnibarMessage { message, is_error: true }, 8000)
+            });
+            html! {<SVGResult file={f.clone()} {progress} {selected_nodes} {selected_edges} add_filter={add_filter.clone()} {show_error} insts_info_link={self.insts_info_link.clone()}/>}
         }).unwrap_or_else(|| {
             html!{<homepage::Homepage {is_canary}/>}
         });
@@ -670,9 +736,11 @@ impl Component for FileDataComponent {
             <nav class="sidebar" ref={sidebar}>
                 <header class={header_class}><img src="html/logo_side_small.png" class="brand"/><div ref={&self.sidebar_button} class="sidebar-button" onclick={hide_sidebar}><MatIconButton icon="menu"></MatIconButton></div></header>
                 <input type="file" ref={&self.file_select} class="trace_file" accept=".log" onchange={on_change} multiple=false/>
+                <input type="file" ref={&self.baseline_select} class="trace_file" accept=".log" onchange={on_baseline_change} multiple=false/>
                 <div class="sidebar-scroll"><div class="sidebar-scroll-container">
                     <SidebarSectionHeader header_text="Navigation" collapsed_text="Open a new trace" section={self.navigation_section.clone()}><ul>
                         <li><a href="#" draggable="false" id="open_trace_file"><div class="material-icons"><MatIcon>{"folder_open"}</MatIcon></div>{"Open trace file"}</a></li>
+                        <li><a href="#" draggable="false" id="open_baseline_file" title="Show only the instantiations which are not in another trace"><div class="material-icons"><MatIcon>{"compare"}</MatIcon></div>{"Compare to baseline trace"}</a></li>
                     </ul></SidebarSectionHeader>
                     {current_trace}
                     <SidebarSectionHeader header_text="Support" collapsed_text="Documentation & Bugs"><ul>
@@ -688,7 +756,7 @@ impl Component for FileDataComponent {
                     </div>
                 </div></div>
             </nav>
-            <Topbar progress={self.progress.clone()} {message} omnibox={self.omnibox.clone()} {search} {pick} {select} {pick_nth_ml} />
+            <Topbar progress={self.progress.clone()} {message} omnibox={self.omnibox.clone()} {search} {pick} {select} {line} {pick_nth_ml} />
             <div class="alerts"></div>
             <div class={page_class}>
                 {page}
@@ -701,22 +769,38 @@ impl Component for FileDataComponent {
                 }
     }
 
-    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+    #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         if first_render {
+            // Traces can be shared by linking to `?trace=https://...`.
+            #[cfg(target_arch = "wasm32")]
+            if let Some(url) = gloo::utils::window()
+                .location()
+                .search()
+                .ok()
+                .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+                .and_then(|params| params.get("trace"))
+            {
+                self.load_url(url, ctx.link());
+            }
+
             // Do this instead of `onclick` when creating `open_trace_file`
-            // above. Otherwise we run into the error here:
+            // and `open_baseline_file` above. Otherwise we run into the error here:
             // https://github.com/leptos-rs/leptos/issues/2104 due to the `.click()`.
-            let input = self.file_select.cast::<HtmlInputElement>().unwrap();
-            let closure: Closure<dyn Fn(MouseEvent)> = Closure::new(move |e: MouseEvent| {
-                e.prevent_default();
-                input.click();
-            });
-            let div = gloo::utils::document()
-                .get_element_by_id("open_trace_file")
-                .unwrap();
-            div.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
-                .unwrap();
-            closure.forget();
+            for (select, id) in [
+                (&self.file_select, "open_trace_file"),
+                (&self.baseline_select, "open_baseline_file"),
+            ] {
+                let input = select.cast::<HtmlInputElement>().unwrap();
+                let closure: Closure<dyn Fn(MouseEvent)> = Closure::new(move |e: MouseEvent| {
+                    e.prevent_default();
+                    input.click();
+                });
+                let div = gloo::utils::document().get_element_by_id(id).unwrap();
+                div.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+                    .unwrap();
+                closure.forget();
+            }
         }
     }
 }
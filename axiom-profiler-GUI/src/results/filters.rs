@@ -1,5 +1,5 @@
 use petgraph::{
-    visit::{Dfs, Walker},
+    visit::{Dfs, Topo, Walker},
     Direction, Graph,
 };
 use smt_log_parser::{
@@ -9,7 +9,7 @@ use smt_log_parser::{
         InstGraph, RawNodeIndex,
     },
     display_with::{DisplayCtxt, DisplayWithCtxt},
-    items::QuantIdx,
+    items::{InstKey, QuantIdx},
     Z3Parser,
 };
 
@@ -19,6 +19,8 @@ pub const DEFAULT_FILTER_CHAIN: &[Filter] = &[
     Filter::IgnoreTheorySolving,
     Filter::MaxInsts(DEFAULT_NODE_COUNT),
 ];
+/// The number of source nodes sampled by [`Filter::TopCentrality`].
+pub const CENTRALITY_SAMPLES: usize = 128;
 pub const DEFAULT_DISABLER_CHAIN: &[(Disabler, bool)] = &[
     (Disabler::Smart, true),
     (Disabler::ENodes, false),
@@ -26,23 +28,97 @@ pub const DEFAULT_DISABLER_CHAIN: &[(Disabler, bool)] = &[
     (Disabler::AllEqualities, false),
 ];
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Filter {
     MaxNodeIdx(usize),
     MinNodeIdx(usize),
     IgnoreTheorySolving,
     IgnoreQuantifier(Option<QuantIdx>),
     IgnoreAllButQuantifier(Option<QuantIdx>),
+    /// Hides the instantiations of all quantifiers in the set.
+    IgnoreQuantifiers(Vec<QuantIdx>),
+    /// Hides the instantiations of all quantifiers not in the set, including
+    /// theory solving. An empty set hides all instantiations.
+    OnlyQuantifiers(Vec<QuantIdx>),
     MaxInsts(usize),
+    /// Keeps the nodes whose cost ranks between the two percentiles, given
+    /// in percent since the filter needs to be hashable, see
+    /// [`InstGraph::keep_cost_percentile`].
+    CostPercentile(u8, u8),
     MaxBranching(usize),
-    ShowNeighbours(RawNodeIndex, Direction),
+    /// Keeps the given number of nodes with the highest subtree heights, see
+    /// [`RawInstGraph::subtree_heights`](smt_log_parser::analysis::raw::RawInstGraph::subtree_heights).
+    MaxSubtreeHeight(u32),
+    /// Keeps the given number of nodes with the highest (approximate)
+    /// betweenness centrality, see [`InstGraph::path_centrality`].
+    TopCentrality(usize),
+    ShowNeighbours(RawNodeIndex, #[serde(with = "serde_direction")] Direction),
     VisitSourceTree(RawNodeIndex, bool),
     VisitSubTreeWithRoot(RawNodeIndex, bool),
     MaxDepth(usize),
+    /// Hides instantiations below the given decision level, see
+    /// [`Instantiation::decision_level`] for how it is counted. Other nodes
+    /// are kept only if they were produced (indirectly) by a kept
+    /// instantiation.
+    ///
+    /// [`Instantiation::decision_level`]: smt_log_parser::items::Instantiation::decision_level
+    MinDecisionLevel(u32),
     ShowLongestPath(RawNodeIndex),
+    /// Shows only the nodes on a single shortest path from a root to a leaf
+    /// through the node, see [`RawInstGraph::shortest_path_through`].
+    ShowShortestPath(RawNodeIndex),
+    /// Shows only the chain of instantiations starting at the node where each
+    /// is strictly deeper than the previous one, see
+    /// [`InstGraph::monotone_depth_chain`].
+    ShowDepthStaircase(RawNodeIndex),
+    /// Shows only the given number of nodes closest to the node, see
+    /// [`RawInstGraph::nearest`].
+    ShowNearest(RawNodeIndex, usize),
+    /// Like [`Filter::ShowNearest`] but dims all other nodes instead of
+    /// hiding them, so that the layout of the graph does not change, see
+    /// [`RawInstGraph::set_dimmed`].
+    FocusNearest(RawNodeIndex, usize),
+    /// Hides a single node, added by ctrl+clicking it in the graph.
+    HideNode(RawNodeIndex),
+    /// Shows a single node, undoing an earlier [`Filter::HideNode`].
+    ShowNode(RawNodeIndex),
     ShowNamedQuantifier(String),
+    /// Shows only instantiations where a bound, resulting or yielded term
+    /// applies the given function symbol, see
+    /// [`Z3Parser::insts_containing_symbol`].
+    ContainsSymbol(String),
     SelectNthMatchingLoop(usize),
     ShowMatchingLoopSubgraph,
+    ConnectSelected(Vec<RawNodeIndex>),
+    /// Unlike the other filters, this reads the current visible graph rather
+    /// than the raw graph: each of the given number of rounds hides the nodes
+    /// with no visible children, peeling off one layer of leaves at a time.
+    HideLeaves(usize),
+    /// Hides the inner nodes of chains of visible nodes with a single parent
+    /// and child, leaving an indirect edge in their place, see
+    /// [`InstGraph::contract_chains`].
+    ContractChains,
+    /// Hides the visible nodes which are only connected to the rest of the
+    /// graph by indirect edges, see [`InstGraph::hide_indirectly_connected`].
+    DirectlyConnectedOnly,
+    /// Shows only the instantiations which are not in a baseline trace,
+    /// given by the sorted keys of its instantiations, see
+    /// [`InstGraph::show_only_new_vs`].
+    OnlyNewVs(Vec<InstKey>),
+    /// The instantiation graph is acyclic, so cycles are looked for at the
+    /// level of quantifiers and their trigger patterns: hides all
+    /// instantiations except those of triggers which lie on a cycle in
+    /// [`InstGraph::collapse_by_trigger`].
+    OnlyCyclic,
+    /// Hides all instantiations outside of the k-core, see
+    /// [`InstGraph::k_core`].
+    KCore(usize),
+    /// Keeps only the earliest (by line) instantiation of each quantifier,
+    /// theory solving instantiations are treated as one more quantifier.
+    FirstPerQuantifier,
+    /// Hides all instantiations which did not (indirectly) feed into a
+    /// conflict clause, see [`InstGraph::conflict_relevant_nodes`].
+    OnlyConflictRelevant,
 }
 
 impl Filter {
@@ -86,8 +162,97 @@ impl Filter {
                             .is_some_and(|i| parser[parser[i].match_].kind.quant_idx() != qidx)
                     })
             }
+            Filter::IgnoreQuantifiers(qidxs) => {
+                let qidxs: fxhash::FxHashSet<_> = qidxs.into_iter().collect();
+                graph
+                    .raw
+                    .set_visibility_when(true, |_: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some_and(|i| {
+                            parser[parser[i].match_]
+                                .kind
+                                .quant_idx()
+                                .is_some_and(|q| qidxs.contains(&q))
+                        })
+                    })
+            }
+            Filter::OnlyQuantifiers(qidxs) => {
+                let qidxs: fxhash::FxHashSet<_> = qidxs.into_iter().collect();
+                graph
+                    .raw
+                    .set_visibility_when(true, |_: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some_and(|i| {
+                            !parser[parser[i].match_]
+                                .kind
+                                .quant_idx()
+                                .is_some_and(|q| qidxs.contains(&q))
+                        })
+                    })
+            }
+            Filter::OnlyCyclic => {
+                let cyclic = graph.cyclic_trigger_insts(parser);
+                graph
+                    .raw
+                    .set_visibility_when(true, |_: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some_and(|i| !cyclic.contains(&i))
+                    })
+            }
+            Filter::KCore(k) => {
+                let core: fxhash::FxHashSet<_> = graph.k_core(k).into_iter().collect();
+                graph
+                    .raw
+                    .set_visibility_when(true, |idx: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some() && !core.contains(&idx)
+                    })
+            }
+            Filter::FirstPerQuantifier => {
+                // Instantiations are in the order of their lines in the log.
+                let mut first = fxhash::FxHashMap::default();
+                for (i, inst) in parser.instantiations() {
+                    first
+                        .entry(parser[inst.match_].kind.quant_idx())
+                        .or_insert(i);
+                }
+                let first: fxhash::FxHashSet<_> = first.into_values().collect();
+                graph
+                    .raw
+                    .set_visibility_when(true, |_: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some_and(|i| !first.contains(&i))
+                    })
+            }
+            Filter::OnlyConflictRelevant => {
+                let relevant: fxhash::FxHashSet<_> =
+                    graph.conflict_relevant_nodes(parser).into_iter().collect();
+                graph
+                    .raw
+                    .set_visibility_when(true, |idx: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some() && !relevant.contains(&idx)
+                    })
+            }
             Filter::MaxInsts(n) => graph.keep_first_n_cost(n),
+            Filter::CostPercentile(lo, hi) => {
+                if let Err(err) = graph.keep_cost_percentile(lo as f32 / 100.0, hi as f32 / 100.0) {
+                    return FilterOutput::Error(format!(
+                        "Cannot show the cost percentiles {lo}%-{hi}%: {err:?}"
+                    ));
+                }
+            }
             Filter::MaxBranching(n) => graph.keep_first_n_children(n),
+            Filter::MaxSubtreeHeight(n) => {
+                let heights = graph.raw.subtree_heights();
+                let mut nodes: Vec<_> = graph.raw.node_indices().collect();
+                nodes.sort_by(|&a, &b| heights[b].cmp(&heights[a]).then_with(|| a.cmp(&b)));
+                graph.raw.keep_first_n(nodes.into_iter(), n as usize)
+            }
+            Filter::TopCentrality(n) => {
+                let centrality = graph.path_centrality(CENTRALITY_SAMPLES);
+                let mut nodes: Vec<_> = graph.raw.node_indices().collect();
+                nodes.sort_by(|&a, &b| {
+                    centrality[b]
+                        .total_cmp(&centrality[a])
+                        .then_with(|| a.cmp(&b))
+                });
+                graph.raw.keep_first_n(nodes.into_iter(), n)
+            }
             Filter::ShowNeighbours(nidx, direction) => {
                 let nodes = graph.raw.neighbors_directed(nidx, direction);
                 graph.raw.set_visibility_many(false, nodes.into_iter())
@@ -106,6 +271,22 @@ impl Filter {
                     .collect();
                 graph.raw.set_visibility_many(!retain, nodes.into_iter())
             }
+            Filter::MinDecisionLevel(level) => {
+                let raw = &*graph.raw.graph;
+                let mut keep = vec![false; raw.node_count()];
+                let mut topo = Topo::new(raw);
+                while let Some(node) = topo.next(raw) {
+                    keep[node.index()] = match raw[node].kind().inst() {
+                        Some(i) => parser[i].decision_level >= level,
+                        None => raw
+                            .neighbors_directed(node, Direction::Incoming)
+                            .any(|parent| keep[parent.index()]),
+                    };
+                }
+                graph
+                    .raw
+                    .set_visibility_when(true, |idx: RawNodeIndex, _: &Node| !keep[idx.0.index()])
+            }
             Filter::MaxDepth(depth) => graph
                 .raw
                 .set_visibility_when(true, |_: RawNodeIndex, node: &Node| {
@@ -114,8 +295,35 @@ impl Filter {
             Filter::ShowLongestPath(nidx) => {
                 return FilterOutput::LongestPath(graph.raw.show_longest_path_through(nidx))
             }
+            Filter::ShowShortestPath(nidx) => {
+                let path = graph.raw.shortest_path_through(nidx);
+                graph.raw.set_visible_set(&path.iter().copied().collect());
+                return FilterOutput::LongestPath(path);
+            }
+            Filter::ShowDepthStaircase(nidx) => {
+                let chain = graph.monotone_depth_chain(nidx);
+                graph.raw.set_visible_set(&chain.iter().copied().collect());
+                return FilterOutput::LongestPath(chain);
+            }
+            Filter::ShowNearest(nidx, n) => {
+                let nearest = graph.raw.nearest(nidx, n);
+                let nearest = nearest.into_iter().map(|(n, _)| n).collect();
+                graph.raw.set_visible_set(&nearest)
+            }
+            Filter::FocusNearest(nidx, n) => {
+                let nearest = graph.raw.nearest(nidx, n);
+                let nearest: fxhash::FxHashSet<_> = nearest.into_iter().map(|(n, _)| n).collect();
+                let dimmed = graph
+                    .raw
+                    .node_indices()
+                    .filter(|n| !nearest.contains(n))
+                    .collect();
+                graph.raw.set_dimmed(dimmed)
+            }
             Filter::ShowNamedQuantifier(name) => {
                 let ctxt = config(parser);
+                let normalizer = ctxt.config.quant_name_normalizer;
+                let name = normalizer.normalize(&name);
                 graph
                     .raw
                     .set_visibility_when(false, |_: RawNodeIndex, node: &Node| {
@@ -123,11 +331,25 @@ impl Filter {
                             parser[parser[i].match_]
                                 .kind
                                 .quant_idx()
-                                .map(|q| parser[q].kind.with(&ctxt).to_string())
-                                .is_some_and(|s| s == name)
+                                .map(|q| {
+                                    parser[q]
+                                        .preferred_kind(ctxt.config.prefer_qid)
+                                        .with(&ctxt)
+                                        .to_string()
+                                })
+                                .is_some_and(|s| normalizer.normalize(&s) == name)
                         })
                     })
             }
+            Filter::ContainsSymbol(symbol) => {
+                // The GUI never parses a summary, so this is always `Some`.
+                let insts = parser.insts_containing_symbol(&symbol).unwrap_or_default();
+                graph
+                    .raw
+                    .set_visibility_when(true, |_: RawNodeIndex, node: &Node| {
+                        node.kind().inst().is_some_and(|i| !insts.contains(&i))
+                    })
+            }
             // TODO: implement
             Filter::SelectNthMatchingLoop(n) => {
                 graph.raw.reset_visibility_to(true);
@@ -198,9 +420,120 @@ impl Filter {
                 //     }
                 // }
             }
+            Filter::ConnectSelected(nodes) => {
+                let nodes = graph.steiner_like_subgraph(&nodes);
+                graph.raw.set_visible_set(&nodes.into_iter().collect())
+            }
+            Filter::HideNode(nidx) => graph.raw.set_visibility_many(true, std::iter::once(nidx)),
+            Filter::ShowNode(nidx) => graph.raw.set_visibility_many(false, std::iter::once(nidx)),
+            Filter::HideLeaves(layers) => {
+                for _ in 0..layers {
+                    let visible = graph.to_visible();
+                    let leaves: Vec<_> = visible
+                        .graph
+                        .externals(Direction::Outgoing)
+                        .map(|n| visible.graph[n].idx)
+                        .collect();
+                    if leaves.is_empty() {
+                        break;
+                    }
+                    graph.raw.set_visibility_many(true, leaves.into_iter())
+                }
+            }
+            Filter::ContractChains => {
+                graph.contract_chains();
+            }
+            Filter::DirectlyConnectedOnly => {
+                graph.hide_indirectly_connected();
+            }
+            Filter::OnlyNewVs(baseline) => {
+                let baseline = baseline.into_iter().collect();
+                graph.show_only_new_vs(parser, &baseline);
+            }
         }
         FilterOutput::None
     }
+    /// Checks that the node and quantifier indices of the filter exist in the
+    /// current file. Filters loaded from a preset may have been created on a
+    /// different file.
+    pub fn is_valid_for(&self, node_count: usize, quant_count: usize) -> bool {
+        let node = |n: &RawNodeIndex| n.0.index() < node_count;
+        let quant = |q: &QuantIdx| usize::from(*q) < quant_count;
+        match self {
+            Filter::IgnoreQuantifier(qidx) | Filter::IgnoreAllButQuantifier(qidx) => match qidx {
+                Some(q) => quant(q),
+                None => true,
+            },
+            Filter::IgnoreQuantifiers(qidxs) | Filter::OnlyQuantifiers(qidxs) => {
+                qidxs.iter().all(quant)
+            }
+            Filter::ShowNeighbours(nidx, _)
+            | Filter::VisitSourceTree(nidx, _)
+            | Filter::VisitSubTreeWithRoot(nidx, _)
+            | Filter::ShowLongestPath(nidx)
+            | Filter::ShowShortestPath(nidx)
+            | Filter::ShowDepthStaircase(nidx)
+            | Filter::ShowNearest(nidx, _)
+            | Filter::FocusNearest(nidx, _)
+            | Filter::HideNode(nidx)
+            | Filter::ShowNode(nidx) => node(nidx),
+            Filter::ConnectSelected(nidxs) => nidxs.iter().all(node),
+            _ => true,
+        }
+    }
+    /// Translates the node indices of the filter with `map`, e.g. to those of
+    /// a different graph of the same trace. Returns `None` if any of them
+    /// cannot be mapped, in which case the filter should be dropped. Filters
+    /// without node indices are returned as they are.
+    pub fn remap_indices(
+        &self,
+        map: impl Fn(RawNodeIndex) -> Option<RawNodeIndex>,
+    ) -> Option<Filter> {
+        Some(match self {
+            Filter::ShowNeighbours(nidx, dir) => Filter::ShowNeighbours(map(*nidx)?, *dir),
+            Filter::VisitSourceTree(nidx, retain) => Filter::VisitSourceTree(map(*nidx)?, *retain),
+            Filter::VisitSubTreeWithRoot(nidx, retain) => {
+                Filter::VisitSubTreeWithRoot(map(*nidx)?, *retain)
+            }
+            Filter::ShowLongestPath(nidx) => Filter::ShowLongestPath(map(*nidx)?),
+            Filter::ShowShortestPath(nidx) => Filter::ShowShortestPath(map(*nidx)?),
+            Filter::ShowDepthStaircase(nidx) => Filter::ShowDepthStaircase(map(*nidx)?),
+            Filter::ShowNearest(nidx, n) => Filter::ShowNearest(map(*nidx)?, *n),
+            Filter::FocusNearest(nidx, n) => Filter::FocusNearest(map(*nidx)?, *n),
+            Filter::HideNode(nidx) => Filter::HideNode(map(*nidx)?),
+            Filter::ShowNode(nidx) => Filter::ShowNode(map(*nidx)?),
+            Filter::ConnectSelected(nidxs) => {
+                Filter::ConnectSelected(nidxs.iter().map(|&n| map(n)).collect::<Option<_>>()?)
+            }
+            _ => self.clone(),
+        })
+    }
+    /// Combines this filter with `next` if applying both in a row is the
+    /// same as applying a single filter: the stricter of two limits of the
+    /// same kind, or just one of two identical filters. Returns `None` if
+    /// they cannot be combined.
+    pub fn merge(&self, next: &Filter) -> Option<Filter> {
+        Some(match (self, next) {
+            (Filter::MaxNodeIdx(a), Filter::MaxNodeIdx(b)) => Filter::MaxNodeIdx(*a.min(b)),
+            (Filter::MinNodeIdx(a), Filter::MinNodeIdx(b)) => Filter::MinNodeIdx(*a.max(b)),
+            (Filter::MaxInsts(a), Filter::MaxInsts(b)) => Filter::MaxInsts(*a.min(b)),
+            (Filter::MaxBranching(a), Filter::MaxBranching(b)) => Filter::MaxBranching(*a.min(b)),
+            (Filter::MaxSubtreeHeight(a), Filter::MaxSubtreeHeight(b)) => {
+                Filter::MaxSubtreeHeight(*a.min(b))
+            }
+            (Filter::TopCentrality(a), Filter::TopCentrality(b)) => {
+                Filter::TopCentrality(*a.min(b))
+            }
+            (Filter::MaxDepth(a), Filter::MaxDepth(b)) => Filter::MaxDepth(*a.min(b)),
+            (Filter::MinDecisionLevel(a), Filter::MinDecisionLevel(b)) => {
+                Filter::MinDecisionLevel(*a.max(b))
+            }
+            // Each round peels off the leaves of the previous one.
+            (Filter::HideLeaves(a), Filter::HideLeaves(b)) => Filter::HideLeaves(a + b),
+            _ if self.get_hash() == next.get_hash() => self.clone(),
+            _ => return None,
+        })
+    }
     pub fn get_hash(&self) -> u64 {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
@@ -210,10 +543,32 @@ impl Filter {
     }
 }
 
+/// `petgraph::Direction` does not implement serde, store it as its name.
+mod serde_direction {
+    use petgraph::Direction;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(dir: &Direction, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(match dir {
+            Direction::Incoming => "Incoming",
+            Direction::Outgoing => "Outgoing",
+        })
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Direction, D::Error> {
+        match String::deserialize(d)?.as_str() {
+            "Incoming" => Ok(Direction::Incoming),
+            "Outgoing" => Ok(Direction::Outgoing),
+            other => Err(D::Error::unknown_variant(other, &["Incoming", "Outgoing"])),
+        }
+    }
+}
+
 pub enum FilterOutput {
     LongestPath(Vec<RawNodeIndex>),
     MatchingLoopGeneralizedTerms(Vec<String>),
     MatchingLoopGraph(Graph<MLGraphNode, ()>),
+    /// The filter could not be applied, the message is shown to the user.
+    Error(String),
     None,
 }
 
@@ -0,0 +1,246 @@
+use gloo::file::{Blob, ObjectUrl};
+use petgraph::{
+    dot::{Config, Dot},
+    visit::EdgeRef,
+};
+use smt_log_parser::{
+    analysis::{
+        raw::{Node, NodeKind},
+        visible::{VisibleInstGraph, VisibleNode},
+        InstGraph, RawNodeIndex, VisibleEdgeIndex,
+    },
+    display_with::{DisplayCtxt, DisplayWithCtxt},
+    NonMaxU32,
+};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::results::{
+    node_info::{EdgeInfo, NodeInfo},
+    svg_result::{QuantIdxToColourMap, NODE_COLOUR_SATURATION, NODE_COLOUR_VALUE},
+};
+
+/// The length after which the terms of equalities shown on edges are cut
+/// off, see [`DisplayConfiguration::inline_equalities`](smt_log_parser::display_with::DisplayConfiguration::inline_equalities).
+const EDGE_LABEL_CHAR_LIMIT: u32 = 20;
+
+/// Presentation attributes replacing the `style.css` rule for selected nodes
+/// and edges, which does not apply outside of the app.
+const SELECTED_ATTRS: &str = " stroke-width=\"3\"";
+/// Same as [`SELECTED_ATTRS`] but for dimmed nodes and the edges touching
+/// them.
+const DIMMED_ATTRS: &str = " opacity=\"0.2\"";
+
+/// Turns the `svg_text` of a `RenderedGraph` into a standalone SVG file which
+/// looks the same as in the app. The selection and dimming are only tracked
+/// as classes of the DOM elements, so they are applied here from
+/// `selected_nodes`, `selected_edges` and the dimmed nodes of `graph`. Node
+/// colours and edge styles are already set by graphviz as presentation
+/// attributes.
+pub fn standalone_svg(
+    svg_text: &str,
+    graph: &VisibleInstGraph,
+    selected_nodes: &[RawNodeIndex],
+    selected_edges: &[VisibleEdgeIndex],
+) -> String {
+    let mut svg = svg_text.to_string();
+    let graph = &graph.graph;
+    let selected = selected_nodes
+        .iter()
+        .map(|n| format!("id=\"node_{}\"", n.0.index()))
+        .chain(
+            selected_edges
+                .iter()
+                .map(|e| format!("id=\"edge_{}\"", e.0.index())),
+        )
+        .map(|id| (id, SELECTED_ATTRS));
+    let dimmed = graph
+        .node_weights()
+        .filter(|n| n.dimmed)
+        .map(|n| format!("id=\"node_{}\"", n.idx.0.index()))
+        .chain(
+            graph
+                .edge_indices()
+                .filter(|&e| {
+                    graph
+                        .edge_endpoints(e)
+                        .is_some_and(|(from, to)| graph[from].dimmed || graph[to].dimmed)
+                })
+                .map(|e| format!("id=\"edge_{}\"", e.index())),
+        )
+        .map(|id| (id, DIMMED_ATTRS));
+    for (id, attrs) in selected.chain(dimmed) {
+        if let Some(at) = svg.find(&id) {
+            svg.insert_str(at + id.len(), attrs);
+        }
+    }
+    if !svg.contains("xmlns=") {
+        svg = svg.replacen("<svg", "<svg xmlns=\"http://www.w3.org/2000/svg\"", 1);
+    }
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n{svg}")
+}
+
+/// Starts a download of `svg` as a file called `file_name`.
+pub fn download_svg(svg: &str, file_name: &str) {
+    let blob = Blob::new_with_options(svg, Some("image/svg+xml"));
+    let url = ObjectUrl::from(blob);
+    let document = gloo::utils::document();
+    let Ok(link) = document.create_element("a") else {
+        return;
+    };
+    let _ = link.set_attribute("href", &url);
+    let _ = link.set_attribute("download", file_name);
+    if let Ok(link) = link.dyn_into::<HtmlElement>() {
+        link.click();
+    }
+}
+
+/// How graphviz draws a node, returned by the `style_fn` of [`graph_dot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStyle {
+    pub label: String,
+    /// The graphviz `style` of the node, e.g. `filled,dashed`.
+    pub style: Option<&'static str>,
+    pub shape: Option<&'static str>,
+    pub fillcolor: Option<String>,
+}
+
+impl NodeStyle {
+    /// The styling of the app: instantiations are coloured by their
+    /// quantifier, dashed if found by MBQI and shaped by whether they have
+    /// hidden parents or children. Enodes are grey and equalities white.
+    pub fn default_for(
+        node: &Node,
+        data: &VisibleNode,
+        ctxt: &DisplayCtxt,
+        colour_map: &QuantIdxToColourMap,
+    ) -> Self {
+        let mut label = node.kind().to_string();
+        if ctxt.config.display_line_numbers {
+            if let Some(line_no) = node.kind().line_no(ctxt.parser) {
+                label = format!("{label}\\nL{line_no}");
+            }
+        }
+        let mut style = Self {
+            label,
+            style: Some("filled"),
+            shape: None,
+            fillcolor: Some("white".to_string()),
+        };
+        match node.kind() {
+            NodeKind::Instantiation(inst) => {
+                let mkind = &ctxt.parser[ctxt.parser[*inst].match_].kind;
+                if mkind.is_mbqi() {
+                    style.style = Some("filled,dashed");
+                }
+                style.shape = Some(match (data.hidden_children, data.hidden_parents) {
+                    (0, 0) => "box",
+                    (0, _) => "house",
+                    (_, 0) => "invhouse",
+                    (_, _) => "diamond",
+                });
+                let hue = colour_map.get_rbg_hue(mkind.quant_idx()) / 360.0;
+                style.fillcolor = Some(format!(
+                    "{hue} {NODE_COLOUR_SATURATION} {NODE_COLOUR_VALUE}"
+                ));
+            }
+            NodeKind::ENode(..) => style.fillcolor = Some("lightgrey".to_string()),
+            _ => (),
+        }
+        style
+    }
+}
+
+/// Turns the visible `graph` into a graphviz digraph with the given
+/// `settings`. The look of each node is decided by `style_fn`, pass
+/// [`NodeStyle::default_for`] for the styling of the app. The ids, classes
+/// and tooltips which the app relies on are always set.
+pub fn graph_dot(
+    settings: &[&str],
+    graph: &VisibleInstGraph,
+    inst_graph: &InstGraph,
+    ctxt: &DisplayCtxt,
+    style_fn: impl Fn(RawNodeIndex, &Node, &VisibleNode) -> NodeStyle,
+) -> String {
+    let components = inst_graph.weak_components();
+    format!(
+        "digraph {{\n{}\n{:?}\n}}",
+        settings.join("\n"),
+        Dot::with_attr_getters(
+            &graph.graph,
+            &[
+                Config::EdgeNoLabel,
+                Config::NodeNoLabel,
+                Config::GraphContentOnly
+            ],
+            &|fg, edge_data| {
+                let (from, to) = (fg[edge_data.source()].idx, fg[edge_data.target()].idx);
+                let edge = edge_data.weight();
+                let kind = &edge.kind(inst_graph);
+                let info = EdgeInfo {
+                    edge,
+                    kind,
+                    from,
+                    to,
+                    graph: inst_graph,
+                    ctxt,
+                };
+                let tooltip = info.tooltip();
+                let is_indirect = edge_data.weight().is_indirect(inst_graph);
+                let style = match is_indirect {
+                    true => "dashed",
+                    false => "solid",
+                };
+                let class = match is_indirect {
+                    true => "indirect",
+                    false => "direct",
+                };
+                let arrowhead = match kind.blame(inst_graph) {
+                    NodeKind::GivenEquality(..) | NodeKind::TransEquality(_) => "empty",
+                    _ => "normal",
+                };
+                let label = kind
+                    .given_equality()
+                    .filter(|_| ctxt.config.inline_equalities)
+                    .map(|(eq, _)| {
+                        let mut ctxt = DisplayCtxt {
+                            parser: ctxt.parser,
+                            term_display: ctxt.term_display,
+                            config: ctxt.config.clone(),
+                        };
+                        ctxt.config.enode_char_limit = NonMaxU32::new(EDGE_LABEL_CHAR_LIMIT);
+                        format!(" label=\"{}\"", eq.with(&ctxt))
+                    })
+                    .unwrap_or_default();
+                format!(
+                    "id=edge_{} tooltip=\"{tooltip}\" style={style} class={class} arrowhead={arrowhead}{label}",
+                    // For edges the `id` is the `VisibleEdgeIndex` from the VisibleGraph!
+                    edge_data.id().index(),
+                )
+            },
+            &|_, (_, data)| {
+                let node_data = &inst_graph.raw[data.idx];
+                let info = NodeInfo {
+                    node: node_data,
+                    ctxt,
+                };
+                let tooltip = info.tooltip(false, None);
+                let NodeStyle {
+                    label,
+                    style,
+                    shape,
+                    fillcolor,
+                } = style_fn(data.idx, node_data, data);
+                let idx = data.idx.0.index();
+                let component = components[data.idx];
+                let style = style.map(|s| format!(" style=\"{s}\"")).unwrap_or_default();
+                let shape = shape.map(|s| format!(" shape={s}")).unwrap_or_default();
+                let fillcolor = fillcolor
+                    .map(|s| format!(" fillcolor=\"{s}\""))
+                    .unwrap_or_default();
+                // For nodes the `id` is the `RawNodeIndex` from the original graph!
+                format!("id=node_{idx} class=\"component-{component}\" tooltip=\"{tooltip}\" label=\"{label}\"{style}{shape}{fillcolor}")
+            },
+        )
+    )
+}
@@ -9,10 +9,12 @@ use web_sys::{Element, HtmlInputElement, ResizeObserver, ResizeObserverEntry};
 use yew::prelude::*;
 
 use crate::commands::{Command, CommandRef, CommandsContext};
+use crate::results::filters::Filter;
 use crate::results::svg_result::RenderedGraph;
 use crate::state::StateContext;
 use crate::{CallbackRef, GlobalCallbacksContext, PagePosition, PrecisePosition};
 
+use super::export;
 use super::svg_graph::{Graph, Svg};
 
 pub enum Msg {
@@ -28,6 +30,9 @@ pub enum Msg {
     KeyHold(()),
     Resize(Vec<ResizeObserverEntry>),
     FocusSelection,
+    /// Centers on the next (or previous if `false`) of the selected nodes.
+    FocusNextNode(bool),
+    DownloadSvg,
     ScrollZoomSelection(Vec<RawNodeIndex>, Vec<VisibleEdgeIndex>),
     ScrollSelection(Vec<RawNodeIndex>, Vec<VisibleEdgeIndex>),
 }
@@ -44,9 +49,12 @@ pub struct GraphContainer {
     zoom_with_mouse: bool,
     held_keys: FxHashMap<String, (Instant, f64, Option<Instant>)>,
     timeout: Option<Interval>,
+    /// Position in `selected_nodes` of the node last centered on with
+    /// `FocusNextNode`.
+    focused_node: Option<usize>,
     _callback_refs: [CallbackRef; 4],
-    _command_refs: [CommandRef; 1],
-    _command_selection: [CommandRef; 2],
+    _command_refs: [CommandRef; 2],
+    _command_selection: [CommandRef; 4],
 }
 
 pub type ResizeObserverPair = (ResizeObserver, Closure<dyn Fn(Vec<ResizeObserverEntry>)>);
@@ -58,6 +66,13 @@ impl GraphContainer {
         self.zoom_with_mouse = with_mouse;
         self.zoom_factor = zoom_factor;
     }
+
+    /// Scrolls (and zooms in, up to a zoom factor of 1) so that `node` is in
+    /// the center of the view.
+    pub fn center_on(&self, ctx: &Context<Self>, node: RawNodeIndex) {
+        let msg = Msg::ScrollZoomSelection(vec![node], Vec::new());
+        ctx.link().send_message(msg);
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -140,6 +155,7 @@ pub struct GraphContainerProps {
     pub rendered: Option<RenderedGraph>,
     pub update_selected_nodes: Callback<RawNodeIndex>,
     pub update_selected_edges: Callback<VisibleEdgeIndex>,
+    pub add_filter: Callback<Filter>,
     pub deselect_all: Callback<()>,
     pub select_all: Callback<()>,
     pub selected_nodes: Vec<RawNodeIndex>,
@@ -181,7 +197,14 @@ impl Component for GraphContainer {
             disabled: false,
         };
         let select_all = (commands)(select_all);
-        let _command_refs = [select_all];
+        let download_svg = Command {
+            name: "Download graph as SVG".to_string(),
+            execute: ctx.link().callback(|_| Msg::DownloadSvg),
+            keyboard_shortcut: vec![],
+            disabled: false,
+        };
+        let download_svg = (commands)(download_svg);
+        let _command_refs = [select_all, download_svg];
         let deselect_all = Command {
             name: "Deselect".to_string(),
             execute: ctx.props().deselect_all.clone(),
@@ -196,7 +219,21 @@ impl Component for GraphContainer {
             disabled: true,
         };
         let focus_selection = (commands)(focus_selection);
-        let _command_selection = [deselect_all, focus_selection];
+        let focus_next = Command {
+            name: "Focus next selected node".to_string(),
+            execute: ctx.link().callback(|_| Msg::FocusNextNode(true)),
+            keyboard_shortcut: vec!["n"],
+            disabled: true,
+        };
+        let focus_next = (commands)(focus_next);
+        let focus_prev = Command {
+            name: "Focus previous selected node".to_string(),
+            execute: ctx.link().callback(|_| Msg::FocusNextNode(false)),
+            keyboard_shortcut: vec!["p"],
+            disabled: true,
+        };
+        let focus_prev = (commands)(focus_prev);
+        let _command_selection = [deselect_all, focus_selection, focus_next, focus_prev];
 
         let held_keys = FxHashMap::default();
         Self {
@@ -210,6 +247,7 @@ impl Component for GraphContainer {
             zoom_with_mouse: false,
             held_keys,
             timeout: None,
+            focused_node: None,
             _callback_refs,
             _command_selection,
             _command_refs,
@@ -232,6 +270,9 @@ impl Component for GraphContainer {
             old_props.selected_nodes.is_empty() && old_props.selected_edges.is_empty();
         let new_no_selection =
             ctx.props().selected_nodes.is_empty() && ctx.props().selected_edges.is_empty();
+        if old_props.selected_nodes != ctx.props().selected_nodes {
+            self.focused_node = None;
+        }
         if old_no_selection != new_no_selection {
             for c in &self._command_selection {
                 c.set_disabled(new_no_selection);
@@ -340,6 +381,10 @@ impl Component for GraphContainer {
                         ctx.link().send_message(Msg::FocusSelection);
                         false
                     }
+                    "n" | "p" if plain => {
+                        ctx.link().send_message(Msg::FocusNextNode(key == "n"));
+                        false
+                    }
                     "Escape" if plain => {
                         ctx.props().deselect_all.emit(());
                         false
@@ -416,6 +461,35 @@ impl Component for GraphContainer {
                 ctx.link().send_message(msg);
                 false
             }
+            Msg::DownloadSvg => {
+                let props = ctx.props();
+                if let Some(rendered) = &props.rendered {
+                    let svg = export::standalone_svg(
+                        &rendered.svg_text,
+                        &rendered.graph,
+                        &props.selected_nodes,
+                        &props.selected_edges,
+                    );
+                    export::download_svg(&svg, "graph.svg");
+                }
+                false
+            }
+            Msg::FocusNextNode(forward) => {
+                let selected = &ctx.props().selected_nodes;
+                if selected.is_empty() {
+                    return false;
+                }
+                let len = selected.len();
+                let next = match (self.focused_node, forward) {
+                    (None, true) => 0,
+                    (None, false) => len - 1,
+                    (Some(i), true) => (i + 1) % len,
+                    (Some(i), false) => (i + len - 1) % len,
+                };
+                self.focused_node = Some(next);
+                self.center_on(ctx, selected[next]);
+                false
+            }
             Msg::ScrollZoomSelection(selected_nodes, selected_edges) => {
                 let Some((min, max)) = get_bounding_rect(&selected_nodes, &selected_edges) else {
                     return false;
@@ -498,6 +572,7 @@ impl Component for GraphContainer {
                 rendered={ctx.props().rendered.clone()}
                 update_selected_nodes={&ctx.props().update_selected_nodes}
                 update_selected_edges={&ctx.props().update_selected_edges}
+                add_filter={&ctx.props().add_filter}
                 zoom_factor={self.zoom_factor}
                 zoom_factor_delta={self.zoom_factor_delta}
                 zoom_with_mouse={self.zoom_with_mouse}
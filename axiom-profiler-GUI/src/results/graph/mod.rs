@@ -1,2 +1,3 @@
+pub mod export;
 pub mod graph_container;
 pub mod svg_graph;
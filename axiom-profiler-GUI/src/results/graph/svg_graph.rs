@@ -7,6 +7,7 @@ use web_sys::{Element, SvgsvgElement};
 use yew::prelude::*;
 use yew::{function_component, html};
 
+use crate::results::filters::Filter;
 use crate::results::svg_result::RenderedGraph;
 use crate::{mouse_position, PrecisePosition};
 
@@ -15,6 +16,8 @@ pub struct GraphProps {
     pub rendered: Option<RenderedGraph>,
     pub update_selected_nodes: Callback<RawNodeIndex>,
     pub update_selected_edges: Callback<VisibleEdgeIndex>,
+    /// Ctrl+clicking a node hides it by emitting [`Filter::HideNode`].
+    pub add_filter: Callback<Filter>,
     pub zoom_factor: f64,
     pub zoom_factor_delta: f64,
     pub zoom_with_mouse: bool,
@@ -194,9 +197,60 @@ pub fn Graph(props: &GraphProps) -> Html {
         );
     }
 
+    {
+        // Whenever the graph is re-rendered, we want to fade out the dimmed nodes
+        // and any edges connected to them
+        let div_ref = div_ref.clone();
+        let rendered = props.rendered.clone();
+
+        use_effect_with_deps(
+            move |generation| {
+                let Some(rendered) = rendered.filter(|_| generation.is_some()) else {
+                    return;
+                };
+                let graph = &rendered.graph.graph;
+                let div = div_ref
+                    .cast::<Element>()
+                    .expect("div_ref not attached to div element");
+                let dimmed: FxHashSet<_> = graph
+                    .node_weights()
+                    .filter(|n| n.dimmed)
+                    .map(|n| n.idx)
+                    .collect();
+                let descendant_nodes = div.get_elements_by_class_name("node");
+                for i in 0..descendant_nodes.length() {
+                    let node = descendant_nodes.item(i).unwrap();
+                    let idx = node.id().strip_prefix("node_").unwrap().parse::<usize>();
+                    let idx = RawNodeIndex(NodeIndex::new(idx.unwrap()));
+                    if dimmed.contains(&idx) {
+                        let _ = node.class_list().add_1("dimmed");
+                    } else {
+                        let _ = node.class_list().remove_1("dimmed");
+                    }
+                }
+                let descendant_edges = div.get_elements_by_class_name("edge");
+                for i in 0..descendant_edges.length() {
+                    let edge = descendant_edges.item(i).unwrap();
+                    let idx = edge.id().strip_prefix("edge_").unwrap().parse::<usize>();
+                    let idx = VisibleEdgeIndex(EdgeIndex::new(idx.unwrap()));
+                    let is_dimmed = graph
+                        .edge_endpoints(idx.0)
+                        .is_some_and(|(from, to)| graph[from].dimmed || graph[to].dimmed);
+                    if is_dimmed {
+                        let _ = edge.class_list().add_1("dimmed");
+                    } else {
+                        let _ = edge.class_list().remove_1("dimmed");
+                    }
+                }
+            },
+            generation,
+        );
+    }
+
     {
         let nodes_callback = props.update_selected_nodes.clone();
         let edges_callback = props.update_selected_edges.clone();
+        let add_filter = props.add_filter.clone();
 
         let div_ref = div_ref.clone();
         use_effect_with_deps(
@@ -237,11 +291,18 @@ pub fn Graph(props: &GraphProps) -> Html {
                             let idx = RawNodeIndex(NodeIndex::new(idx.unwrap()));
                             // attach event listener to node
                             let callback = nodes_callback.clone();
+                            let add_filter = add_filter.clone();
                             let mousedown: Closure<dyn Fn(Event)> =
                                 Closure::new(move |e: Event| {
                                     e.cancel_bubble();
                                     e.stop_propagation();
-                                    callback.emit(idx);
+                                    if e.dyn_ref::<web_sys::MouseEvent>()
+                                        .is_some_and(|e| e.ctrl_key())
+                                    {
+                                        add_filter.emit(Filter::HideNode(idx));
+                                    } else {
+                                        callback.emit(idx);
+                                    }
                                 });
                             node.add_event_listener_with_callback(
                                 "mousedown",
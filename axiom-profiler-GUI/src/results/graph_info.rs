@@ -8,6 +8,7 @@ use smt_log_parser::analysis::{RawNodeIndex, VisibleEdgeIndex};
 use yew::prelude::*;
 
 use super::{
+    filters::Filter,
     graph::graph_container,
     node_info::{SelectedEdgesInfo, SelectedNodesInfo},
     svg_result::RenderedGraph,
@@ -75,6 +76,8 @@ pub struct GraphInfoProps {
     pub update_selected_nodes: Callback<Vec<RawNodeIndex>>,
     pub selected_edges: Vec<VisibleEdgeIndex>,
     pub update_selected_edges: Callback<Vec<VisibleEdgeIndex>>,
+    /// Filters added directly from the graph, e.g. by ctrl+clicking a node.
+    pub add_filter: Callback<Filter>,
     pub outdated: bool,
 }
 
@@ -267,6 +270,7 @@ impl Component for GraphInfo {
                     rendered={ctx.props().rendered.clone()}
                     update_selected_nodes={&on_node_select}
                     update_selected_edges={&on_edge_select}
+                    add_filter={&ctx.props().add_filter}
                     {select_all}
                     {deselect_all}
                     selected_nodes={self.selected_nodes.keys().copied().collect::<Vec<RawNodeIndex>>()}
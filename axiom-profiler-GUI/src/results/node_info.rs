@@ -6,8 +6,8 @@ use smt_log_parser::{
         visible::{VisibleEdge, VisibleEdgeKind},
         InstGraph, RawNodeIndex, VisibleEdgeIndex,
     },
-    display_with::{DisplayCtxt, DisplayWithCtxt},
-    items::{MatchKind, VarNames},
+    display_with::{DisplayConfiguration, DisplayCtxt, DisplayWithCtxt},
+    items::{MatchKind, QuantIdx, VarNames},
     NonMaxU32,
 };
 use yew::{
@@ -18,6 +18,19 @@ use crate::{configuration::ConfigurationProvider, state::StateProvider};
 
 use super::svg_result::RenderedGraph;
 
+/// The name of `quant` as shown in the UI, derived from its trigger if
+/// configured to do so.
+pub fn quant_label(ctxt: &DisplayCtxt, quant: QuantIdx) -> String {
+    if ctxt.config.derived_quant_labels {
+        ctxt.parser.derived_quant_label(quant, ctxt.config.clone())
+    } else {
+        ctxt.parser[quant]
+            .preferred_kind(ctxt.config.prefer_qid)
+            .with(ctxt)
+            .to_string()
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct InfoLineProps {
     header: String,
@@ -88,16 +101,14 @@ impl<'a, 'b> NodeInfo<'a, 'b> {
             NodeKind::GivenEquality(eq, _) => eq.with(&ctxt).to_string(),
             NodeKind::TransEquality(eq) => eq.with(&ctxt).to_string(),
             NodeKind::Instantiation(inst) => match &ctxt.parser[ctxt.parser[inst].match_].kind {
-                MatchKind::MBQI { quant, .. } => ctxt.parser[*quant].kind.with(&ctxt).to_string(),
+                MatchKind::MBQI { quant, .. } => quant_label(&ctxt, *quant),
                 MatchKind::TheorySolving { axiom_id, .. } => {
                     let namespace = &ctxt.parser[axiom_id.namespace];
                     let id = axiom_id.id.map(|id| id.to_string()).unwrap_or_default();
                     format!("{namespace}[{id}]")
                 }
-                MatchKind::Axiom { axiom, .. } => ctxt.parser[*axiom].kind.with(&ctxt).to_string(),
-                MatchKind::Quantifier { quant, .. } => {
-                    ctxt.parser[*quant].kind.with(&ctxt).to_string()
-                }
+                MatchKind::Axiom { axiom, .. } => quant_label(&ctxt, *axiom),
+                MatchKind::Quantifier { quant, .. } => quant_label(&ctxt, *quant),
             },
         }
     }
@@ -170,7 +181,20 @@ impl<'a, 'b> NodeInfo<'a, 'b> {
         // The resulting term is of the form `quant-inst(¬(quant) ∨ (inst))`.
         let resulting_term_or = *self.ctxt.parser[resulting_term].child_ids.first()?;
         let resulting_term = *self.ctxt.parser[resulting_term_or].child_ids.get(1)?;
-        Some(resulting_term.with(self.ctxt).to_string())
+        // Highlight where the trigger was matched in the instantiated body.
+        let ctxt = DisplayCtxt {
+            parser: self.ctxt.parser,
+            term_display: self.ctxt.term_display,
+            config: DisplayConfiguration {
+                highlight_terms: self
+                    .ctxt
+                    .parser
+                    .instantiated_triggers(inst)
+                    .unwrap_or_default(),
+                ..self.ctxt.config.clone()
+            },
+        };
+        Some(resulting_term.with(&ctxt).to_string())
     }
     pub fn yield_terms(&self) -> Option<Vec<String>> {
         let NodeKind::Instantiation(inst) = *self.node.kind() else {
@@ -261,6 +285,10 @@ pub fn SelectedNodesInfo(
                 }).collect();
                 html! { <>{yields}<hr/></> }
             });
+            let yield_usage = info.node.kind().inst().map(|i| {
+                let (used, total) = parser.borrow().yield_usage(i);
+                html! { <InfoLine header="Yields Used" text={format!("{used} of {total}")} code=false /> }
+            });
             html! {
                 <details {open}>
                 <summary {onclick}>{summary}{description}</summary>
@@ -270,6 +298,7 @@ pub fn SelectedNodesInfo(
                     {bound_terms}
                     {resulting_term}
                     {yield_terms}
+                    {yield_usage}
                     <InfoLine header="Cost" text={format!("{:.1}{}", info.node.cost, z3_gen.unwrap_or_default())} code=false />
                     <InfoLine header="To Root" text={format!("short {}, long {}", info.node.fwd_depth.min, info.node.fwd_depth.max)} code=false />
                     <InfoLine header="To Leaf" text={format!("short {}, long {}", info.node.bwd_depth.min, info.node.bwd_depth.max)} code=false />
@@ -320,10 +349,18 @@ impl<'a, 'b> EdgeInfo<'a, 'b> {
         format!("{} {arrow} {}", from.index(), to.index())
     }
     pub fn kind(&self) -> String {
+        let trigger_total = || {
+            self.graph.raw[self.to]
+                .kind()
+                .inst()
+                .and_then(|i| self.kind.trigger_position(i, self.ctxt.parser))
+                .map(|(_, total)| format!(" of {total}"))
+                .unwrap_or_default()
+        };
         match self.kind {
             VisibleEdgeKind::Direct(_, EdgeKind::Yield) => "Yield".to_string(),
             VisibleEdgeKind::Direct(_, EdgeKind::Blame { trigger_term }) => {
-                format!("Blame trigger #{trigger_term}")
+                format!("Blame trigger #{trigger_term}{}", trigger_total())
             }
             VisibleEdgeKind::Direct(_, EdgeKind::BlameEq { .. }) => "Blame Equality".to_string(),
             VisibleEdgeKind::Direct(_, EdgeKind::EqualityFact) => "Equality Fact".to_string(),
@@ -339,14 +376,14 @@ impl<'a, 'b> EdgeInfo<'a, 'b> {
                 (!forward).then_some("Reverse ").unwrap_or_default()
             ),
             VisibleEdgeKind::YieldBlame { trigger_term, .. } => {
-                format!("Yield/Blame trigger #{trigger_term}")
+                format!("Yield/Blame trigger #{trigger_term}{}", trigger_total())
             }
             VisibleEdgeKind::YieldEq(_) => "Yield Equality".to_string(),
             VisibleEdgeKind::YieldBlameEq { .. } => "Yield/Blame Equality".to_string(),
-            VisibleEdgeKind::YieldEqOther(_) => "Yield Equality Other".to_string(),
+            VisibleEdgeKind::YieldEqOther { .. } => "Yield Equality Other".to_string(),
             VisibleEdgeKind::ENodeEq(_) => "ENode Equality".to_string(),
             VisibleEdgeKind::ENodeBlameEq { .. } => "ENode/Blame Equality".to_string(),
-            VisibleEdgeKind::ENodeEqOther(_) => "ENode Equality Other".to_string(),
+            VisibleEdgeKind::ENodeEqOther { .. } => "ENode Equality Other".to_string(),
             VisibleEdgeKind::Unknown(start, end) => {
                 let ctxt = self.ctxt;
                 let hidden_from = self.graph.raw.graph.edge_endpoints(start.0).unwrap().1;
@@ -409,6 +446,18 @@ pub fn SelectedEdgesInfo(
         };
         let (from, to) = rendered.graph.graph.edge_endpoints(edge.0).unwrap();
         let (from, to) = (rendered.graph.graph[from].idx, rendered.graph.graph[to].idx);
+        let merged_paths = match rendered.graph.merged_paths(edge).len() {
+            0 => None,
+            merged => Some(html! {
+                <InfoLine header="Hidden Paths" text={(merged + 1).to_string()} code=false />
+            }),
+        };
+        let hidden_nodes = match rendered.graph.indirect_path(edge, &graph).len() {
+            0 => None,
+            hidden => Some(html! {
+                <InfoLine header="Hidden Nodes" text={hidden.to_string()} code=false />
+            }),
+        };
         let edge = &rendered.graph[edge];
         let kind = &edge.kind(&graph);
         let info = EdgeInfo {
@@ -428,11 +477,20 @@ pub fn SelectedEdgesInfo(
             ctxt,
         };
         let blame = blame.tooltip(true, None);
+        let equality_steps = match info.kind.equality_steps(&graph) {
+            0 => None,
+            steps => Some(html! {
+                <InfoLine header="Equality Steps" text={steps.to_string()} code=false />
+            }),
+        };
         html! {
             <details {open} {onclick}>
                 <summary>{summary}</summary>
                 <ul>
                     <InfoLine header="Blamed" text={blame} code=true />
+                    {equality_steps}
+                    {hidden_nodes}
+                    {merged_paths}
                 </ul>
             </details>
         }
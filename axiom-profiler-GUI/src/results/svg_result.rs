@@ -3,8 +3,9 @@ use crate::{
     filters,
     results::{
         filters::FilterOutput,
+        graph::export::{self, NodeStyle},
         graph_info::{GraphInfo, Msg as GraphInfoMsg},
-        node_info::{EdgeInfo, NodeInfo},
+        node_info::quant_label,
     },
     state::StateContext,
     OpenedFileInfo,
@@ -19,13 +20,13 @@ use material_yew::{dialog::MatDialog, WeakComponentLink};
 use palette::{encoding::Srgb, white_point::D65, FromColor, Hsluv, Hsv, LuvHue};
 use petgraph::{
     dot::{Config, Dot},
-    visit::EdgeRef,
     Graph,
 };
 use smt_log_parser::{
     analysis::{
-        analysis::matching_loop::MLGraphNode, raw::NodeKind, visible::VisibleInstGraph, InstGraph,
-        RawNodeIndex, VisibleEdgeIndex,
+        analysis::matching_loop::MLGraphNode,
+        visible::{EdgeVisibility, VisibleInstGraph},
+        InstGraph, RawNodeIndex, VisibleEdgeIndex,
     },
     display_with::{DisplayCtxt, DisplayWithCtxt},
     items::QuantIdx,
@@ -62,6 +63,7 @@ pub enum Msg {
     UpdateSvgText(AttrValue, VisibleInstGraph),
     SetPermission(GraphDimensions),
     SetDisabled(Vec<Disabler>),
+    SetEdgeVisibility(EdgeVisibility),
     RenderGraph,
     ApplyFilter(Filter),
     ResetGraph,
@@ -100,6 +102,9 @@ pub struct SVGResult {
     calculated: Option<VisibleInstGraph>,
     /// The calculated graph is moved here once rendered.
     rendered: Option<RenderedGraph>,
+    /// Which edges to keep in the calculated graph, independent of the
+    /// visibility of the nodes.
+    edge_visibility: EdgeVisibility,
 
     graph_warning: WeakComponentLink<MatDialog>,
     graph_dim: GraphDimensions,
@@ -118,6 +123,8 @@ pub struct SVGProps {
     pub progress: Callback<GraphState>,
     pub selected_nodes: Callback<Vec<RawNodeIndex>>,
     pub selected_edges: Callback<Vec<VisibleEdgeIndex>>,
+    pub add_filter: Callback<Filter>,
+    pub show_error: Callback<String>,
     pub insts_info_link: WeakComponentLink<GraphInfo>,
 }
 
@@ -166,6 +173,7 @@ impl Component for SVGResult {
         Self {
             calculated: None,
             rendered: None,
+            edge_visibility: EdgeVisibility::default(),
             graph_warning: WeakComponentLink::default(),
             graph_dim: GraphDimensions {
                 node_count: 0,
@@ -187,6 +195,23 @@ impl Component for SVGResult {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::ConstructedGraph(parser) => {
+                let equality_nodes = parser.borrow().raw.equality_nodes();
+                let cfg = ctx.link().get_configuration().unwrap();
+                let limit = cfg.config.auto_disable_equalities.threshold();
+                if limit.is_some_and(|limit| equality_nodes > limit) {
+                    // Patch the initial disablers which are still queued, so
+                    // that the full graph is never rendered.
+                    for msg in &mut self.queue {
+                        if let Msg::SetDisabled(disablers) = msg {
+                            if !disablers.contains(&Disabler::AllEqualities) {
+                                disablers.push(Disabler::AllEqualities);
+                            }
+                        }
+                    }
+                    if let Some(filter) = ctx.props().file.filter.borrow().as_ref() {
+                        filter.send_message(filters::Msg::AutoDisabledEqualities(equality_nodes));
+                    }
+                }
                 self.constructed_graph = Some(parser);
                 let queue = std::mem::take(&mut self.queue);
                 ctx.props()
@@ -247,6 +272,10 @@ impl Component for SVGResult {
                         ctx.link().send_message(Msg::RenderMLGraph(graph));
                         false
                     }
+                    FilterOutput::Error(error) => {
+                        ctx.props().show_error.emit(error);
+                        false
+                    }
                     FilterOutput::None => false,
                 }
             }
@@ -273,6 +302,7 @@ impl Component for SVGResult {
             // }
             Msg::ResetGraph => {
                 inst_graph.raw.reset_visibility_to(false);
+                inst_graph.raw.set_dimmed(Default::default());
                 false
             }
             Msg::SetPermission(dim) => {
@@ -283,19 +313,36 @@ impl Component for SVGResult {
                 Disabler::apply(disablers.iter().copied(), inst_graph, &parser.borrow());
                 false
             }
+            Msg::SetEdgeVisibility(edges) => {
+                self.edge_visibility = edges;
+                false
+            }
             Msg::RenderGraph => {
-                if self
-                    .rendered
-                    .as_ref()
-                    .is_some_and(|r| inst_graph.visible_unchanged(&r.graph))
-                {
+                let inline_equalities = ctx
+                    .link()
+                    .get_configuration()
+                    .unwrap()
+                    .config
+                    .display
+                    .inline_equalities;
+                let unchanged = |g: &VisibleInstGraph| {
+                    inst_graph.visible_unchanged(g)
+                        && g.edge_visibility() == self.edge_visibility
+                        && g.inline_equalities() == inline_equalities
+                };
+                if self.rendered.as_ref().is_some_and(|r| unchanged(&r.graph)) {
                     return false;
                 }
-                let calculated = self
-                    .calculated
-                    .take()
-                    .filter(|c| inst_graph.visible_unchanged(c));
-                let calculated = calculated.unwrap_or_else(|| inst_graph.to_visible());
+                let calculated = self.calculated.take().filter(|c| unchanged(c));
+                let calculated = calculated.unwrap_or_else(|| {
+                    let mut calculated = if inline_equalities {
+                        inst_graph.to_visible_inline_equalities()
+                    } else {
+                        inst_graph.to_visible()
+                    };
+                    calculated.retain_edges(self.edge_visibility);
+                    calculated
+                });
                 let (node_count, edge_count) =
                     (calculated.graph.node_count(), calculated.graph.edge_count());
                 self.graph_dim.node_count = node_count;
@@ -315,7 +362,6 @@ impl Component for SVGResult {
                     ctx.props()
                         .progress
                         .emit(GraphState::Rendering(RenderingState::GraphToDot));
-                    let filtered_graph = &calculated.graph;
                     let cfg = ctx.link().get_configuration().unwrap();
                     let ctxt = &DisplayCtxt {
                         parser: &parser.borrow(),
@@ -330,7 +376,9 @@ impl Component for SVGResult {
                     // `ranksep` dictates the distance between ranks (rows) in the graph,
                     // it should be set dynamically based on the average number of children
                     // per node out of all nodes with at least one child.
+                    let rankdir = format!("rankdir={};", ctxt.config.layout_direction.rankdir());
                     let settings = [
+                        rankdir.as_str(),
                         "ranksep=1.0;",
                         "splines=false;",
                         "nslimit=6;",
@@ -340,103 +388,13 @@ impl Component for SVGResult {
                         // "pack=32;",
                         // "packMode=\"graph\";",
                     ];
-                    let dot_output = format!(
-                        "digraph {{\n{}\n{:?}\n}}",
-                        settings.join("\n"),
-                        Dot::with_attr_getters(
-                            filtered_graph,
-                            &[
-                                Config::EdgeNoLabel,
-                                Config::NodeNoLabel,
-                                Config::GraphContentOnly
-                            ],
-                            &|fg, edge_data| {
-                                let (from, to) =
-                                    (fg[edge_data.source()].idx, fg[edge_data.target()].idx);
-                                let edge = edge_data.weight();
-                                let kind = &edge.kind(inst_graph);
-                                let info = EdgeInfo {
-                                    edge,
-                                    kind,
-                                    from,
-                                    to,
-                                    graph: &*inst_graph,
-                                    ctxt,
-                                };
-                                let tooltip = info.tooltip();
-                                let is_indirect = edge_data.weight().is_indirect(inst_graph);
-                                let style = match is_indirect {
-                                    true => "dashed",
-                                    false => "solid",
-                                };
-                                let class = match is_indirect {
-                                    true => "indirect",
-                                    false => "direct",
-                                };
-                                let arrowhead = match kind.blame(inst_graph) {
-                                    NodeKind::GivenEquality(..) | NodeKind::TransEquality(_) => {
-                                        "empty"
-                                    }
-                                    _ => "normal",
-                                };
-                                format!(
-                                    "id=edge_{} tooltip=\"{tooltip}\" style={style} class={class} arrowhead={arrowhead}",
-                                    // For edges the `id` is the `VisibleEdgeIndex` from the VisibleGraph!
-                                    edge_data.id().index(),
-                                )
-                            },
-                            &|_, (_, data)| {
-                                let node_data = &inst_graph.raw[data.idx];
-                                let info = NodeInfo {
-                                    node: node_data,
-                                    ctxt,
-                                };
-                                let tooltip = info.tooltip(false, None);
-                                let mut style = Some("filled");
-                                let mut shape = None;
-                                let mut fillcolor = Some("white".to_string());
-                                let label = node_data.kind().to_string();
-                                match node_data.kind() {
-                                    NodeKind::Instantiation(inst) => {
-                                        let mkind = &(&*parser.borrow())
-                                            [(&*parser.borrow())[*inst].match_]
-                                            .kind;
-                                        style = Some(if mkind.is_mbqi() {
-                                            "filled,dashed"
-                                        } else {
-                                            "filled"
-                                        });
-                                        let s = match (data.hidden_children, data.hidden_parents) {
-                                            (0, 0) => "box",
-                                            (0, _) => "house",
-                                            (_, 0) => "invhouse",
-                                            (_, _) => "diamond",
-                                        };
-                                        shape = Some(s);
-                                        let hue =
-                                            rc_parser.colour_map.get_rbg_hue(mkind.quant_idx())
-                                                / 360.0;
-                                        fillcolor = Some(format!(
-                                            "{hue} {NODE_COLOUR_SATURATION} {NODE_COLOUR_VALUE}"
-                                        ));
-                                    }
-                                    NodeKind::ENode(..) => {
-                                        fillcolor = Some("lightgrey".to_string());
-                                    }
-                                    _ => (),
-                                };
-                                let idx = data.idx.0.index();
-                                let style =
-                                    style.map(|s| format!(" style=\"{s}\"")).unwrap_or_default();
-                                let shape =
-                                    shape.map(|s| format!(" shape={s}")).unwrap_or_default();
-                                let fillcolor = fillcolor
-                                    .map(|s| format!(" fillcolor=\"{s}\""))
-                                    .unwrap_or_default();
-                                // For nodes the `id` is the `RawNodeIndex` from the original graph!
-                                format!("id=node_{idx} tooltip=\"{tooltip}\" label=\"{label}\"{style}{shape}{fillcolor}")
-                            },
-                        )
+                    let colour_map = &rc_parser.colour_map;
+                    let dot_output = export::graph_dot(
+                        &settings,
+                        &calculated,
+                        inst_graph,
+                        ctxt,
+                        |_, node, data| NodeStyle::default_for(node, data, ctxt, colour_map),
                     );
                     ctx.props()
                         .progress
@@ -521,7 +479,9 @@ impl Component for SVGResult {
                 // `ranksep` dictates the distance between ranks (rows) in the graph,
                 // it should be set dynamically based on the average number of children
                 // per node out of all nodes with at least one child.
+                let rankdir = format!("rankdir={};", ctxt.config.layout_direction.rankdir());
                 let settings = [
+                    rankdir.as_str(),
                     "ranksep=1.0;",
                     "splines=false;",
                     "nslimit=6;",
@@ -544,7 +504,7 @@ impl Component for SVGResult {
                                 match &node_data {
                                     MLGraphNode::QI(quant, pattern) => format!(
                                         "{}: {}",
-                                        rc_parser.parser.borrow()[*quant].kind.with(ctxt),
+                                        quant_label(ctxt, *quant),
                                         pattern.with(ctxt)
                                     ),
                                     MLGraphNode::ENode(matched_term) =>
@@ -620,6 +580,7 @@ impl Component for SVGResult {
                 update_selected_nodes={ctx.props().selected_nodes.clone()}
                 selected_edges={ctx.props().file.selected_edges.clone()}
                 update_selected_edges={ctx.props().selected_edges.clone()}
+                add_filter={ctx.props().add_filter.clone()}
             />
             <Warning noderef={self.graph_warning.clone()} onclosed={ctx.link().callback(Msg::UserPermission)} dimensions={self.graph_dim}/></>
         }
@@ -37,6 +37,7 @@ pub fn Shortcuts(props: &ShortcutsProps) -> Html {
                 <h2>{"Other"}</h2>
                 <table>
                     <tr><td><div class="keycap">{"f"}</div> {" (with selection)"}</td><td>{"Scroll + zoom to current selection"}</td></tr>
+                    <tr><td><div class="keycap">{"n"}</div>{"/"}<div class="keycap">{"p"}</div> {" (with selection)"}</td><td>{"Scroll to next/previous selected node"}</td></tr>
                     <tr><td><div class="keycap">{"Cmd"}</div>{" + "}<div class="keycap">{"a"}</div></td><td>{"Select all"}</td></tr>
                     <tr><td><div class="keycap">{"Esc"}</div></td><td>{"Deselect"}</td></tr>
                     <tr><td><div class="keycap">{"Cmd"}</div>{" + "}<div class="keycap">{"s"}</div></td><td>{"Search"}</td></tr>
@@ -0,0 +1,38 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use smt_log_parser::{analysis::InstGraph, LogParser, Z3Parser};
+
+/// A small synthetic trace containing a single-pattern matching loop and a
+/// multi-pattern quantifier which is instantiated every few iterations.
+const SAMPLE: &str = include_str!("sample.log");
+
+fn parse() -> Z3Parser {
+    Z3Parser::from_str(SAMPLE).process_all().unwrap()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parser");
+    group.throughput(Throughput::Bytes(SAMPLE.len() as u64));
+    group.bench_function("parse", |b| b.iter(|| black_box(parse())));
+    group.finish();
+}
+
+fn bench_analysis(c: &mut Criterion) {
+    let parser = parse();
+    c.bench_function("inst_graph_new", |b| {
+        b.iter(|| black_box(InstGraph::new(&parser).unwrap()))
+    });
+    c.bench_function("to_visible", |b| {
+        b.iter_batched_ref(
+            || {
+                let mut graph = InstGraph::new(&parser).unwrap();
+                graph.raw.reset_visibility_to(false);
+                graph
+            },
+            |graph| black_box(graph.to_visible()),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_parse, bench_analysis);
+criterion_main!(benches);
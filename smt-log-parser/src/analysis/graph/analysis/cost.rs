@@ -1,15 +1,18 @@
-use petgraph::Direction;
+use petgraph::{graph::DiGraph, Direction};
 
 use crate::{
     analysis::{
-        raw::{Node, NodeKind},
-        RawNodeIndex,
+        raw::{EdgeKind, Node, NodeKind, RawIx},
+        InstGraph, RawNodeIndex,
     },
-    Z3Parser,
+    items::InstIdx,
+    TiVec, Z3Parser,
 };
 
 use super::{Initialiser, TransferInitialiser};
 
+pub use crate::parsers::z3::cost::cost_order;
+
 pub trait CostInitialiser {
     /// The starting value for a node.
     fn base(&mut self, node: &Node, parser: &Z3Parser) -> f64;
@@ -59,6 +62,8 @@ impl<C: CostInitialiser> TransferInitialiser<false, 0> for C {
     }
 }
 
+/// Assigns the same costs as [`Z3Parser::default_costs`], which
+/// [`InstGraph::initialise_default`] uses directly.
 pub struct DefaultCost;
 impl CostInitialiser for DefaultCost {
     fn base(&mut self, node: &Node, _parser: &Z3Parser) -> f64 {
@@ -87,3 +92,85 @@ impl CostInitialiser for DefaultCost {
         node.cost * incoming[idx] as f64 / total
     }
 }
+
+impl InstGraph {
+    /// Assigns every node its cost from [`Z3Parser::default_costs`], taking
+    /// into account which instantiations are disabled.
+    pub(super) fn assign_default_costs(&mut self, parser: &Z3Parser) {
+        let costs = parser.default_costs(|inst| self.raw[inst].disabled());
+        for node in self.raw.graph.node_weights_mut() {
+            node.cost = match *node.kind() {
+                NodeKind::ENode(enode) => costs.enodes[enode],
+                NodeKind::GivenEquality(eq, use_) => costs.given(eq, use_),
+                NodeKind::TransEquality(eq) => costs.transitive[eq],
+                NodeKind::Instantiation(inst) => costs.insts[inst],
+            };
+        }
+    }
+
+    /// The cost of each instantiation node, as assigned by
+    /// [`Z3Parser::default_costs`] unless replaced with
+    /// [`InstGraph::recompute_costs`].
+    pub fn instantiation_costs(&self) -> TiVec<InstIdx, f64> {
+        // Instantiation nodes are added in order of their `InstIdx`.
+        self.raw
+            .graph
+            .node_weights()
+            .filter(|node| node.kind().inst().is_some())
+            .map(|node| node.cost)
+            .collect()
+    }
+
+    /// The `k` most expensive instantiations, in the order of
+    /// [`Analysis::cost`](super::Analysis::cost).
+    pub fn top_instantiations(&self, k: usize) -> Vec<InstIdx> {
+        self.analysis
+            .cost
+            .iter()
+            .filter_map(|&n| self.raw[n].kind().inst())
+            .take(k)
+            .collect()
+    }
+
+    /// Replaces the cost of every node by the one computed with `cost` and
+    /// re-sorts the nodes by cost, so that operations such as
+    /// [`InstGraph::keep_first_n_cost`] use the new costs straight away. The
+    /// nodes and edges, as well as the depth and children analyses, are left
+    /// as they are.
+    pub fn recompute_costs(&mut self, cost: impl CostInitialiser, parser: &Z3Parser) {
+        self.initialise_transfer(cost, parser);
+        self.analyse_cost();
+    }
+
+    /// Ranks every node under two cost functions, returning `(node, rank_a,
+    /// rank_b)` in node index order. Rank `0` is the most expensive node,
+    /// ties are broken by the lower index as in [`InstGraph::analyse`]. The
+    /// costs assigned to the graph are left unchanged.
+    pub fn cost_correlation(
+        &self,
+        a: impl CostInitialiser,
+        b: impl CostInitialiser,
+        parser: &Z3Parser,
+    ) -> Vec<(RawNodeIndex, usize, usize)> {
+        let mut graph = self.raw.graph.clone();
+        Self::transfer_in(&mut graph, &self.subgraphs, a, parser);
+        let rank_a = cost_ranks(&graph);
+        Self::transfer_in(&mut graph, &self.subgraphs, b, parser);
+        let rank_b = cost_ranks(&graph);
+        self.raw
+            .node_indices()
+            .zip(rank_a.into_iter().zip(rank_b))
+            .map(|(idx, (a, b))| (idx, a, b))
+            .collect()
+    }
+}
+
+fn cost_ranks(graph: &DiGraph<Node, EdgeKind, RawIx>) -> Vec<usize> {
+    let mut order: Vec<_> = graph.node_indices().map(RawNodeIndex).collect();
+    order.sort_by(cost_order(|n: RawNodeIndex| graph[n.0].cost));
+    let mut ranks = vec![0; order.len()];
+    for (rank, idx) in order.into_iter().enumerate() {
+        ranks[idx.0.index()] = rank;
+    }
+    ranks
+}
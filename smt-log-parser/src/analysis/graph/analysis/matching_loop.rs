@@ -1,6 +1,8 @@
+#[cfg(feature = "display")]
+use std::fmt;
 use std::{
     collections::{HashMap, HashSet},
-    ops::Deref,
+    ops::{ControlFlow, Deref},
 };
 
 use fxhash::{FxHashMap, FxHashSet};
@@ -13,10 +15,14 @@ use crate::{
         visible::VisibleEdge,
         InstGraph,
     },
-    display_with::{DisplayCtxt, DisplayWithCtxt},
     items::{ENodeIdx, EqTransIdx, InstIdx, MatchKind, QuantIdx, TermIdx},
     Graph, Z3Parser,
 };
+#[cfg(feature = "display")]
+use crate::{
+    display_with::{DisplayConfiguration, DisplayCtxt, DisplayWithCtxt},
+    formatter::TermDisplayContext,
+};
 // use matching_loop_graph::*;
 
 pub const MIN_MATCHING_LOOP_LENGTH: usize = 3;
@@ -151,6 +157,7 @@ impl AbstractInst {
                 .insert(n, MlEquality::from(from, to, creators));
         }
     }
+    #[cfg(feature = "display")]
     pub fn _to_string(&self, compact: bool, parser: &mut Z3Parser, ctxt: DisplayCtxt) -> String {
         let generalised_pattern = parser
             .terms
@@ -205,6 +212,19 @@ pub enum MLGraphNode {
 
 impl InstGraph {
     pub fn search_matching_loops(&mut self, parser: &mut Z3Parser) -> usize {
+        self.search_matching_loops_with(parser, |_, _, _, _| ControlFlow::Continue(()))
+    }
+
+    /// Same as [`InstGraph::search_matching_loops`] but calls `on_found` with
+    /// the index and end node of each potential matching loop, longest first,
+    /// as soon as the nodes belonging to it are known. Returning
+    /// [`ControlFlow::Break`] stops the search, only the loops found up to
+    /// then are kept.
+    pub fn search_matching_loops_with(
+        &mut self,
+        parser: &mut Z3Parser,
+        mut on_found: impl FnMut(&Self, usize, RawNodeIndex, &mut Z3Parser) -> ControlFlow<()>,
+    ) -> usize {
         let currently_disabled_nodes = self.disabled_nodes();
         self.initialise_inst_succs_and_preds(parser);
         // disable all nodes that do not correspond to QIs
@@ -279,6 +299,7 @@ impl InstGraph {
         });
         // assign to each node in a matching loop which matching loops it belongs to, i.e., if a node is part of the
         // i-th longest matching loop, it stores the index i-1. Do this, by doing a reverse-DFS from all ML end nodes
+        let mut stopped_after = None;
         for (i, end_node) in matching_loop_end_nodes.iter().enumerate() {
             let mut dfs = Dfs::new(
                 petgraph::visit::Reversed(&matching_loop_subgraph.graph),
@@ -289,6 +310,14 @@ impl InstGraph {
                 let orig_nx = matching_loop_subgraph.graph[nx].idx.0;
                 self.raw.graph[orig_nx].part_of_ml.insert(i);
             }
+            let end = matching_loop_subgraph.graph[*end_node].idx;
+            if on_found(self, i, end, parser).is_break() {
+                stopped_after = Some(i + 1);
+                break;
+            }
+        }
+        if let Some(len) = stopped_after {
+            matching_loop_end_nodes.truncate(len);
         }
         // collect all matching loop end nodes
         let matching_loop_end_nodes_raw_indices: Vec<RawNodeIndex> = matching_loop_end_nodes
@@ -569,6 +598,212 @@ impl InstGraph {
     }
 }
 
+/// A potential matching loop, as found by [`InstGraph::find_matching_loops`].
+#[derive(Debug, Clone)]
+pub struct MatchingLoop {
+    /// The quantifier and pattern of each instantiation in one iteration of
+    /// the loop, in the order they happen along `chain`.
+    pub cycle: Vec<(QuantIdx, TermIdx)>,
+    /// The generalisation of all terms matched by the first pattern of the
+    /// cycle (in its first trigger), `None` if they could not be generalised.
+    pub generalised_term: Option<TermIdx>,
+    /// The longest chain of instantiations in the loop, ending at its end node.
+    pub chain: Vec<InstIdx>,
+}
+
+impl MatchingLoop {
+    /// The number of instantiations in one iteration of the loop.
+    pub fn period(&self) -> usize {
+        self.cycle.len()
+    }
+
+    /// Writes a summary of the loop for sharing in bug reports to `md`,
+    /// includes the first two iterations of the example chain.
+    #[cfg(feature = "display")]
+    pub fn to_markdown(
+        &self,
+        md: &mut impl fmt::Write,
+        parser: &Z3Parser,
+        config: DisplayConfiguration,
+    ) -> fmt::Result {
+        let term_display = TermDisplayContext::basic();
+        let ctxt = DisplayCtxt {
+            parser,
+            term_display: &term_display,
+            config,
+        };
+        let quant = |q: QuantIdx| {
+            parser[q]
+                .preferred_kind(ctxt.config.prefer_qid)
+                .with(&ctxt)
+                .to_string()
+        };
+        let cycle = self
+            .cycle
+            .iter()
+            .map(|&(q, pattern)| format!("`{}` on `{}`", quant(q), pattern.with(&ctxt)))
+            .collect::<Vec<_>>()
+            .join(" → ");
+        writeln!(md, "- **Quantifier cycle:** {cycle}")?;
+        match self.generalised_term {
+            Some(term) => writeln!(md, "- **Generalised term:** `{}`", term.with(&ctxt))?,
+            None => writeln!(md, "- **Generalised term:** -")?,
+        }
+        writeln!(
+            md,
+            "- **Period:** {} ({} instantiations in the chain)",
+            self.period(),
+            self.chain.len()
+        )?;
+        writeln!(md, "- **Example chain:**")?;
+        let shown = self.chain.len().min(2 * self.period());
+        for (i, &iidx) in self.chain[..shown].iter().enumerate() {
+            let match_ = &parser[parser[iidx].match_];
+            let name = match_.kind.quant_idx().map(quant).unwrap_or_default();
+            write!(md, "  {}. `{name}`", i + 1)?;
+            if let Some(blame) = match_.trigger_matches().next() {
+                write!(
+                    md,
+                    " matching `{}`",
+                    parser[blame.enode()].owner.with(&ctxt)
+                )?;
+            }
+            writeln!(md)?;
+        }
+        if shown < self.chain.len() {
+            writeln!(md, "  - … and {} more", self.chain.len() - shown)?;
+        }
+        Ok(())
+    }
+}
+
+impl InstGraph {
+    /// Runs [`InstGraph::search_matching_loops`] and summarises each of the
+    /// loops found, longest first.
+    pub fn find_matching_loops(&mut self, parser: &mut Z3Parser) -> Vec<MatchingLoop> {
+        self.find_matching_loops_with(parser, |_| ControlFlow::Continue(()))
+    }
+
+    /// Same as [`InstGraph::find_matching_loops`] but calls `on_found` with
+    /// each loop as soon as the search discovers it. Returning
+    /// [`ControlFlow::Break`] stops the search, only the loops found up to
+    /// then are returned.
+    pub fn find_matching_loops_with(
+        &mut self,
+        parser: &mut Z3Parser,
+        mut on_found: impl FnMut(&MatchingLoop) -> ControlFlow<()>,
+    ) -> Vec<MatchingLoop> {
+        let mut found = Vec::new();
+        self.search_matching_loops_with(parser, |graph, n, end, parser| {
+            let Some(ml) = graph.matching_loop(n, end, parser) else {
+                return ControlFlow::Continue(());
+            };
+            let flow = on_found(&ml);
+            found.push(ml);
+            flow
+        });
+        found
+    }
+
+    /// For each quantifier the length of the longest chain of its
+    /// instantiations in the graph where each depends on the previous one
+    /// (directly or via terms and equalities, not via instantiations of other
+    /// quantifiers). Sorted longest first, a very long chain is a strong hint
+    /// of a matching loop. Quantifiers which were never instantiated are
+    /// left out.
+    pub fn longest_self_chain_per_quant(&self, parser: &Z3Parser) -> Vec<(QuantIdx, usize)> {
+        let mut longest = FxHashMap::<InstIdx, usize>::default();
+        let mut per_quant = FxHashMap::<QuantIdx, usize>::default();
+        // Instantiations only depend on earlier ones, so visiting them in
+        // order finds the longest chain ending at each.
+        for (inst, data) in parser.instantiations() {
+            let Some(quant) = parser[data.match_].kind.quant_idx() else {
+                continue;
+            };
+            let len = self
+                .inst_parents(inst)
+                .into_iter()
+                .filter(|&parent| parser[parser[parent].match_].kind.quant_idx() == Some(quant))
+                .filter_map(|parent| longest.get(&parent).copied())
+                .max()
+                .unwrap_or(0)
+                + 1;
+            longest.insert(inst, len);
+            let best = per_quant.entry(quant).or_default();
+            *best = (*best).max(len);
+        }
+        let mut per_quant: Vec<_> = per_quant.into_iter().collect();
+        per_quant.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        per_quant
+    }
+
+    fn matching_loop(
+        &self,
+        n: usize,
+        end: RawNodeIndex,
+        parser: &mut Z3Parser,
+    ) -> Option<MatchingLoop> {
+        let end = self.raw[end].kind().inst()?;
+        // Instantiations only depend on earlier ones, so visiting them in
+        // order finds the longest chain ending at each.
+        let mut insts: Vec<_> = self
+            .raw
+            .graph
+            .node_weights()
+            .filter(|node| node.part_of_ml.contains(&n))
+            .filter_map(|node| node.kind().inst())
+            .collect();
+        insts.sort_unstable();
+        let mut longest = FxHashMap::<InstIdx, (usize, Option<InstIdx>)>::default();
+        for &inst in &insts {
+            let best = self
+                .inst_parents(inst)
+                .into_iter()
+                .filter_map(|parent| longest.get(&parent).map(|&(len, _)| (len, parent)))
+                .max();
+            let len = best.map_or(0, |(len, _)| len) + 1;
+            longest.insert(inst, (len, best.map(|(_, parent)| parent)));
+        }
+        let mut chain = vec![end];
+        while let Some(&(_, Some(parent))) = longest.get(chain.last().unwrap()) {
+            chain.push(parent);
+        }
+        chain.reverse();
+
+        let keys: Vec<_> = chain
+            .iter()
+            .filter_map(|&i| {
+                let kind = &parser[parser[i].match_].kind;
+                Some((kind.quant_idx()?, kind.pattern()?))
+            })
+            .collect();
+        let period = (1..=keys.len())
+            .find(|&p| (p..keys.len()).all(|i| keys[i] == keys[i - p]))
+            .unwrap_or(0);
+        let cycle = keys[..period].to_vec();
+        let first = cycle.first().copied();
+        let matched: Vec<_> = insts
+            .iter()
+            .filter_map(|&i| {
+                let match_ = &parser[parser[i].match_];
+                let key = (match_.kind.quant_idx()?, match_.kind.pattern()?);
+                (Some(key) == first).then_some(())?;
+                Some(parser[match_.trigger_matches().next()?.enode()].owner)
+            })
+            .collect();
+        let generalised_term = if matched.is_empty() {
+            None
+        } else {
+            parser.terms.generalise(&mut parser.strings, matched)
+        };
+        Some(MatchingLoop {
+            cycle,
+            generalised_term,
+            chain,
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum InstOrEquality {
     Inst(String, MatchKind),
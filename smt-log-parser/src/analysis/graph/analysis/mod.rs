@@ -5,16 +5,19 @@ pub mod next_insts;
 
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::{MemDbg, MemSize};
-use petgraph::Direction;
+use petgraph::{graph::DiGraph, Direction};
 
-use crate::{Graph, Result, Z3Parser};
+use crate::{items::GraphIdx, Graph, Result, TiVec, Z3Parser};
 
 use self::{
-    cost::DefaultCost, depth::DefaultDepth, matching_loop::MLGraphNode,
-    next_insts::DefaultNextInsts,
+    cost::cost_order, depth::DefaultDepth, matching_loop::MLGraphNode, next_insts::DefaultNextInsts,
 };
 
-use super::{raw::Node, InstGraph, RawNodeIndex};
+use super::{
+    raw::{EdgeKind, Node, RawIx},
+    subgraph::Subgraph,
+    InstGraph, RawNodeIndex,
+};
 
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
 #[derive(Debug, Default)]
@@ -98,37 +101,42 @@ impl InstGraph {
         const ID: u8,
     >(
         &mut self,
+        initialiser: I,
+        parser: &Z3Parser,
+    ) {
+        Self::transfer_in(&mut self.raw.graph, &self.subgraphs, initialiser, parser)
+    }
+
+    /// Runs [`InstGraph::initialise_transfer`] on `graph` instead of the
+    /// graph itself, `graph` must have the same nodes and edges.
+    pub(super) fn transfer_in<
+        I: TransferInitialiser<FORWARD, ID>,
+        const FORWARD: bool,
+        const ID: u8,
+    >(
+        graph: &mut DiGraph<Node, EdgeKind, RawIx>,
+        subgraphs: &TiVec<GraphIdx, Subgraph>,
         mut initialiser: I,
         parser: &Z3Parser,
     ) {
         // Reset to base
-        for node in self.raw.graph.node_weights_mut() {
+        for node in graph.node_weights_mut() {
             let base = initialiser.base(node, parser);
             initialiser.assign(node, base);
         }
-        for subgraph in self.subgraphs.iter() {
+        for subgraph in subgraphs.iter() {
             initialiser.reset();
             let for_each = |idx: RawNodeIndex| {
-                let incoming: Vec<_> = self
-                    .raw
-                    .graph
+                let incoming: Vec<_> = graph
                     .neighbors_directed(idx.0, I::direction())
-                    .map(|i| initialiser.observe(&self.raw.graph[i], parser))
+                    .map(|i| initialiser.observe(&graph[i], parser))
                     .collect();
-                let mut neighbors = self
-                    .raw
-                    .graph
-                    .neighbors_directed(idx.0, I::direction())
-                    .detach();
+                let mut neighbors = graph.neighbors_directed(idx.0, I::direction()).detach();
                 let mut i = 0;
-                while let Some((_, neighbor)) = neighbors.next(&self.raw.graph) {
-                    let transfer = initialiser.transfer(
-                        &self.raw.graph[idx.0],
-                        RawNodeIndex(idx.0),
-                        i,
-                        &incoming,
-                    );
-                    initialiser.add(&mut self.raw.graph[neighbor], transfer);
+                while let Some((_, neighbor)) = neighbors.next(graph) {
+                    let transfer =
+                        initialiser.transfer(&graph[idx.0], RawNodeIndex(idx.0), i, &incoming);
+                    initialiser.add(&mut graph[neighbor], transfer);
                     i += 1;
                 }
             };
@@ -141,11 +149,24 @@ impl InstGraph {
         }
     }
     pub fn initialise_default(&mut self, parser: &Z3Parser) {
-        self.initialise_transfer(DefaultCost, parser);
+        self.initialise_default_with_progress(parser, |_| ());
+    }
+    /// Reports progress from 0 to 1 through the cost, depth and ranking
+    /// phases.
+    pub(super) fn initialise_default_with_progress(
+        &mut self,
+        parser: &Z3Parser,
+        mut progress: impl FnMut(f32),
+    ) {
+        self.assign_default_costs(parser);
+        progress(0.3);
         self.initialise_collect(DefaultDepth::<true>, parser);
+        progress(0.5);
         self.initialise_collect(DefaultDepth::<false>, parser);
+        progress(0.7);
 
         self.analyse();
+        progress(1.0);
     }
 
     pub fn initialise_inst_succs_and_preds(&mut self, parser: &Z3Parser) {
@@ -154,13 +175,7 @@ impl InstGraph {
     }
 
     pub fn analyse(&mut self) {
-        self.analysis.cost.sort_by(|&a, &b| {
-            self.raw.graph[a.0]
-                .cost
-                .total_cmp(&self.raw.graph[b.0].cost)
-                .reverse()
-                .then_with(|| a.cmp(&b))
-        });
+        self.analyse_cost();
         self.analysis.children.sort_by(|&a, &b| {
             let ac = self.raw.neighbors_directed(a, Direction::Outgoing).len();
             let bc = self.raw.neighbors_directed(b, Direction::Outgoing).len();
@@ -178,6 +193,13 @@ impl InstGraph {
         //     self.raw.graph[a.0].max_depth.cmp(&self.raw.graph[b.0].max_depth).reverse().then_with(|| a.cmp(&b))
         // );
     }
+
+    fn analyse_cost(&mut self) {
+        let raw = &self.raw;
+        self.analysis
+            .cost
+            .sort_by(cost_order(|n: RawNodeIndex| raw[n].cost));
+    }
 }
 
 // FIXME: `ID` makes the implementations unique, but is not a great solution.
@@ -0,0 +1,174 @@
+use fxhash::{FxHashMap, FxHashSet};
+use petgraph::{
+    algo::tarjan_scc,
+    graph::{DiGraph, NodeIndex},
+    Direction::Incoming,
+};
+
+use crate::{
+    items::{InstIdx, QuantIdx, TermIdx},
+    Z3Parser,
+};
+
+use super::{
+    raw::{IndexesInstGraph, NodeKind},
+    InstGraph, RawNodeIndex,
+};
+
+/// All instantiations of a quantifier due to the same trigger pattern, a node
+/// in the graph returned by [`InstGraph::collapse_by_trigger`].
+#[derive(Debug, Clone)]
+pub struct TriggerMeta {
+    /// `None` for theory solving instantiations.
+    pub quant: Option<QuantIdx>,
+    /// `None` for instantiations not due to a pattern, e.g. MBQI.
+    pub pattern: Option<TermIdx>,
+    /// In the order they were instantiated.
+    pub insts: Vec<InstIdx>,
+}
+
+/// All instantiations of a quantifier, a node in the graph returned by
+/// [`InstGraph::collapse_by_quantifier`].
+#[derive(Debug, Clone)]
+pub struct QuantMeta {
+    /// `None` for theory solving instantiations.
+    pub quant: Option<QuantIdx>,
+    /// The instantiation nodes, in the order they were instantiated.
+    pub nodes: Vec<RawNodeIndex>,
+}
+
+/// The instantiation graph with one node per quantifier, see
+/// [`InstGraph::collapse_by_quantifier`].
+#[derive(Debug, Clone)]
+pub struct QuantCollapsedGraph {
+    pub graph: DiGraph<QuantMeta, u32>,
+}
+
+impl QuantCollapsedGraph {
+    /// The instantiation nodes collapsed into the meta-node `q`. To switch
+    /// from the overview to these, show only the instantiations of
+    /// [`QuantMeta::quant`].
+    pub fn expand_meta(&self, q: NodeIndex) -> &[RawNodeIndex] {
+        &self.graph[q].nodes
+    }
+
+    /// The meta-node of `quant`, if it has any instantiations.
+    pub fn meta_of(&self, quant: Option<QuantIdx>) -> Option<NodeIndex> {
+        self.graph
+            .node_indices()
+            .find(|&q| self.graph[q].quant == quant)
+    }
+}
+
+impl InstGraph {
+    /// Collapses all instantiations with the same quantifier and trigger
+    /// pattern into a single node. For quantifiers with multiple triggers this
+    /// shows which of the triggers is responsible for instantiations. An edge
+    /// between two nodes counts the pairs of instantiations where the second
+    /// depends on a term or equality produced by the first, directly or
+    /// through other terms and equalities. Self loops are included.
+    pub fn collapse_by_trigger(&self, parser: &Z3Parser) -> DiGraph<TriggerMeta, u32> {
+        let mut graph = DiGraph::new();
+        let mut groups = FxHashMap::default();
+        let mut inst_group = FxHashMap::default();
+        for (idx, inst) in parser.instantiations() {
+            let kind = &parser[inst.match_].kind;
+            let (quant, pattern) = (kind.quant_idx(), kind.pattern());
+            let group = *groups.entry((quant, pattern)).or_insert_with(|| {
+                graph.add_node(TriggerMeta {
+                    quant,
+                    pattern,
+                    insts: Vec::new(),
+                })
+            });
+            graph[group].insts.push(idx);
+            inst_group.insert(idx, group);
+        }
+        self.add_collapsed_edges(parser, &inst_group, &mut graph);
+        graph
+    }
+
+    /// Collapses all instantiations of the same quantifier into a single
+    /// node, with edges as in [`InstGraph::collapse_by_trigger`]. The
+    /// instantiations behind each node can be recovered with
+    /// [`QuantCollapsedGraph::expand_meta`].
+    pub fn collapse_by_quantifier(&self, parser: &Z3Parser) -> QuantCollapsedGraph {
+        let mut graph = DiGraph::new();
+        let mut groups = FxHashMap::default();
+        let mut inst_group = FxHashMap::default();
+        for (idx, inst) in parser.instantiations() {
+            let quant = parser[inst.match_].kind.quant_idx();
+            let group = *groups.entry(quant).or_insert_with(|| {
+                graph.add_node(QuantMeta {
+                    quant,
+                    nodes: Vec::new(),
+                })
+            });
+            graph[group].nodes.push(idx.index(&self.raw));
+            inst_group.insert(idx, group);
+        }
+        self.add_collapsed_edges(parser, &inst_group, &mut graph);
+        QuantCollapsedGraph { graph }
+    }
+
+    /// Adds an edge between the groups of each instantiation and of each of
+    /// its [`inst_parents`](Self::inst_parents), weighted by how many such
+    /// pairs there are.
+    fn add_collapsed_edges<N>(
+        &self,
+        parser: &Z3Parser,
+        inst_group: &FxHashMap<InstIdx, NodeIndex>,
+        graph: &mut DiGraph<N, u32>,
+    ) {
+        let mut edges = FxHashMap::<(NodeIndex, NodeIndex), u32>::default();
+        for (idx, _) in parser.instantiations() {
+            let to = inst_group[&idx];
+            for parent in self.inst_parents(idx) {
+                *edges.entry((inst_group[&parent], to)).or_default() += 1;
+            }
+        }
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_unstable_by_key(|&(edge, _)| edge);
+        for ((from, to), count) in edges {
+            graph.add_edge(from, to, count);
+        }
+    }
+
+    /// The instantiations of all quantifier and trigger pattern pairs which
+    /// lie on a cycle in [`InstGraph::collapse_by_trigger`], i.e. those that
+    /// (indirectly) lead to further instantiations of themselves. This is
+    /// where any matching loop must be, since the instantiation graph itself
+    /// is acyclic.
+    pub fn cyclic_trigger_insts(&self, parser: &Z3Parser) -> FxHashSet<InstIdx> {
+        let graph = self.collapse_by_trigger(parser);
+        tarjan_scc(&graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || graph.find_edge(scc[0], scc[0]).is_some())
+            .flatten()
+            .flat_map(|group| graph[group].insts.iter().copied())
+            .collect()
+    }
+
+    /// The instantiations which produced a term or equality that `inst`
+    /// depends on, found by walking back until reaching instantiations.
+    pub(super) fn inst_parents(&self, inst: InstIdx) -> FxHashSet<InstIdx> {
+        let mut parents = FxHashSet::default();
+        let mut seen = FxHashSet::default();
+        let mut stack = vec![inst.index(&self.raw)];
+        while let Some(node) = stack.pop() {
+            for parent in self.raw.graph.neighbors_directed(node.0, Incoming) {
+                match *self.raw.graph[parent].kind() {
+                    NodeKind::Instantiation(parent) => {
+                        parents.insert(parent);
+                    }
+                    _ => {
+                        if seen.insert(parent) {
+                            stack.push(RawNodeIndex(parent));
+                        }
+                    }
+                }
+            }
+        }
+        parents
+    }
+}
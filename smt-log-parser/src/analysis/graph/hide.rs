@@ -1,10 +1,16 @@
 use petgraph::{
     graph::{DiGraph, EdgeReference, NodeIndex},
-    visit::{Bfs, EdgeFiltered, EdgeRef, Reversed, ReversedEdgeReference, Walker},
+    visit::{Bfs, EdgeFiltered, EdgeRef, IntoNeighbors, Reversed, ReversedEdgeReference, Walker},
+    Direction,
 };
 
+use fxhash::FxHashSet;
+
+use crate::{items::InstKey, Error, Result, Z3Parser};
+
 use super::{
-    raw::{EdgeKind, Node, NodeState, RawInstGraph, RawIx},
+    raw::{EdgeKind, IndexesInstGraph, Node, NodeState, RawInstGraph, RawIx},
+    visible::VisibleEdge,
     InstGraph, RawNodeIndex,
 };
 
@@ -66,6 +72,24 @@ impl RawInstGraph {
             self.set_visibility(hidden, node);
         }
     }
+    /// Makes exactly the nodes in `visible` visible and hides all others, in
+    /// a single pass over the graph. Disabled nodes are left disabled. This
+    /// is equivalent to `reset_visibility_to(true)` followed by
+    /// `set_visibility_many(false, ..)` but only touches nodes whose state
+    /// actually changes.
+    pub fn set_visible_set(&mut self, visible: &FxHashSet<RawNodeIndex>) {
+        for (idx, node) in self.graph.node_weights_mut().enumerate() {
+            if node.disabled() {
+                continue;
+            }
+            let state = if visible.contains(&RawNodeIndex(NodeIndex::new(idx))) {
+                NodeState::Visible
+            } else {
+                NodeState::Hidden
+            };
+            self.stats.set_state(node, state);
+        }
+    }
 
     fn filter_path(
         &self,
@@ -141,12 +165,84 @@ impl RawInstGraph {
         self.set_visibility_many(false, path.iter().copied());
         path
     }
+
+    /// A single shortest path from a root through `node` to a leaf, in order
+    /// from the root. Where several parents (or children) continue a shortest
+    /// path, the one with the lowest index is taken. Unlike
+    /// [`show_longest_path_through`](Self::show_longest_path_through) this
+    /// does not change the visibility of any nodes.
+    pub fn shortest_path_through(&self, node: RawNodeIndex) -> Vec<RawNodeIndex> {
+        let mut path = vec![node];
+        {
+            let to_root = self.path_to_root_graph(false);
+            let mut curr = node;
+            while let Some(parent) = (&to_root).neighbors(curr.0).min() {
+                curr = RawNodeIndex(parent);
+                path.push(curr);
+            }
+        };
+        path.reverse();
+        {
+            let to_leaf = self.path_to_leaf_graph(false);
+            let mut curr = node;
+            while let Some(child) = (&to_leaf).neighbors(curr.0).min() {
+                curr = RawNodeIndex(child);
+                path.push(curr);
+            }
+        };
+        path.retain(|&n| !self.graph[n.0].disabled());
+        path
+    }
 }
 
 impl InstGraph {
+    /// Shows only the instantiations whose [`InstKey`] is not in
+    /// `baseline`, i.e. those which are new compared to a baseline trace
+    /// whose keys were collected with [`Z3Parser::inst_keys`]. All other
+    /// nodes are hidden. Returns the number of new instantiations.
+    pub fn show_only_new_vs(&mut self, parser: &Z3Parser, baseline: &FxHashSet<InstKey>) -> usize {
+        self.raw.reset_visibility_to(true);
+        let new: Vec<_> = parser
+            .instantiations()
+            .filter(|&(i, _)| !baseline.contains(&parser.inst_key(i)))
+            .map(|(i, _)| i.index(&self.raw))
+            .collect();
+        self.raw.set_visibility_many(false, new.iter().copied());
+        new.len()
+    }
+
     pub fn keep_first_n_cost(&mut self, n: usize) {
         self.raw.keep_first_n(self.analysis.cost.iter().copied(), n)
     }
+    /// Keeps only the visible nodes whose cost ranks within the `[lo, hi]`
+    /// percentile band of all visible nodes, e.g. `0.9` and `0.99` keep the
+    /// nodes more expensive than 90% but at most as expensive as 99% of the
+    /// others. The cheapest node is at percentile `0.0` and the most
+    /// expensive at `1.0`.
+    ///
+    /// Returns [`Error::InvalidPercentileBand`] without changing any nodes
+    /// if not `0.0 <= lo <= hi <= 1.0`.
+    pub fn keep_cost_percentile(&mut self, lo: f32, hi: f32) -> Result<()> {
+        if !(0.0 <= lo && lo <= hi && hi <= 1.0) {
+            return Err(Error::InvalidPercentileBand(lo, hi));
+        }
+        let visible: Vec<_> = self
+            .analysis
+            .cost
+            .iter()
+            .copied()
+            .filter(|n| self.raw.graph[n.0].visible())
+            .collect();
+        let last = visible.len().saturating_sub(1).max(1) as f32;
+        // Sorted by cost, most expensive first.
+        for (rank, node) in visible.into_iter().enumerate() {
+            let percentile = 1.0 - rank as f32 / last;
+            if percentile < lo || hi < percentile {
+                self.raw.set_visibility(true, node);
+            }
+        }
+        Ok(())
+    }
     pub fn keep_first_n_children(&mut self, n: usize) {
         self.raw
             .keep_first_n(self.analysis.children.iter().copied(), n)
@@ -158,4 +254,74 @@ impl InstGraph {
     // pub fn keep_first_n_max_depth(&mut self, n: usize) {
     //     self.raw.keep_first_n(self.analysis.max_depth.iter().copied(), n)
     // }
+
+    /// The raw nodes currently marked visible. Unlike `to_visible`, this
+    /// does not build the visible graph, it only reads the nodes' states.
+    pub fn visible_nodes(&self) -> impl Iterator<Item = RawNodeIndex> + '_ {
+        self.raw
+            .node_indices()
+            .filter(|&n| self.raw.graph[n.0].visible())
+    }
+    /// The raw nodes currently hidden. Disabled nodes are neither visible
+    /// nor hidden.
+    pub fn hidden_nodes(&self) -> impl Iterator<Item = RawNodeIndex> + '_ {
+        self.raw
+            .node_indices()
+            .filter(|&n| self.raw.graph[n.0].hidden())
+    }
+    /// Contracts each maximal chain of the visible graph into a single edge
+    /// by hiding every visible node with exactly one visible parent and one
+    /// visible child, whatever its kind. Endpoints and branch points stay
+    /// visible, so once the graph is rebuilt with [`InstGraph::to_visible`]
+    /// each chain becomes an indirect edge, and the number of nodes it stands
+    /// for is the length of its
+    /// [`indirect_path`](super::visible::VisibleInstGraph::indirect_path).
+    /// Returns the number of nodes hidden.
+    pub fn contract_chains(&mut self) -> usize {
+        let visible = self.to_visible();
+        let inner: Vec<_> = visible
+            .graph
+            .node_indices()
+            .filter(|&n| {
+                let parents = visible.graph.edges_directed(n, Direction::Incoming);
+                let children = visible.graph.edges_directed(n, Direction::Outgoing);
+                parents.count() == 1 && children.count() == 1
+            })
+            .map(|n| visible.graph[n].idx)
+            .collect();
+        let hidden = inner.len();
+        self.raw.set_visibility_many(true, inner.into_iter());
+        hidden
+    }
+
+    /// Hides every visible node which is connected to the other visible
+    /// nodes only through indirect edges, i.e. edges which were added by
+    /// [`InstGraph::to_visible`] in place of a path through hidden nodes.
+    /// What remains is the part of the graph held together by direct edges.
+    /// Returns the number of nodes hidden.
+    pub fn hide_indirectly_connected(&mut self) -> usize {
+        let visible = self.to_visible();
+        let floating: Vec<_> = visible
+            .graph
+            .node_indices()
+            .filter(|&n| {
+                !visible
+                    .graph
+                    .edges_directed(n, Direction::Incoming)
+                    .chain(visible.graph.edges_directed(n, Direction::Outgoing))
+                    .any(|e| matches!(e.weight(), VisibleEdge::Direct(_)))
+            })
+            .map(|n| visible.graph[n].idx)
+            .collect();
+        let hidden = floating.len();
+        self.raw.set_visibility_many(true, floating.into_iter());
+        hidden
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.raw.visible_nodes()
+    }
+    pub fn hidden_count(&self) -> usize {
+        self.raw.stats.hidden as usize
+    }
 }
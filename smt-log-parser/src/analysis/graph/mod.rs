@@ -1,13 +1,27 @@
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::{MemDbg, MemSize};
 
-use crate::{items::GraphIdx, Result, TiVec, Z3Parser};
+use std::collections::VecDeque;
 
-use self::{analysis::Analysis, raw::RawInstGraph, subgraph::Subgraph, visible::VisibleInstGraph};
+use fxhash::{FxHashMap, FxHashSet};
+use petgraph::{graph::NodeIndex, Direction};
+
+use crate::{
+    items::{GraphIdx, InstIdx},
+    Result, TiVec, Z3Parser,
+};
+
+use self::{
+    analysis::Analysis,
+    raw::{IndexesInstGraph, RawInstGraph},
+    subgraph::Subgraph,
+    visible::VisibleInstGraph,
+};
 
 // TODO: once the ML algo is reimplemented, delete this
 // pub mod inst_graph;
 pub mod analysis;
+pub mod collapse;
 pub mod disable;
 pub mod generalise;
 pub mod hide;
@@ -28,23 +42,295 @@ pub struct InstGraph {
 
 impl InstGraph {
     pub fn new(parser: &Z3Parser) -> Result<Self> {
-        let mut raw = RawInstGraph::new(parser)?;
+        Self::new_with_progress(parser, |_| ())
+    }
+
+    /// Same as [`InstGraph::new`] but calls `progress` with values from 0 to
+    /// 1 as construction proceeds, first through adding the nodes and edges
+    /// and then through the analysis of depths and costs.
+    pub fn new_with_progress(parser: &Z3Parser, mut progress: impl FnMut(f32)) -> Result<Self> {
+        progress(0.0);
+        let mut raw = RawInstGraph::new_with_progress(parser, |p| progress(0.5 * p))?;
         let subgraphs = raw.partition()?;
+        progress(0.6);
         let analysis = Analysis::new(raw.graph.node_indices().map(RawNodeIndex))?;
         let mut self_ = InstGraph {
             raw,
             subgraphs,
             analysis,
         };
-        self_.initialise_default(parser);
+        self_.initialise_default_with_progress(parser, |p| progress(0.6 + 0.4 * p));
         Ok(self_)
     }
 
+    /// Assigns each node the id of the weakly connected component it is in.
+    /// Components are numbered in order of their lowest node index.
+    pub fn weak_components(&self) -> TiVec<RawNodeIndex, u32> {
+        // Every node with an edge is in exactly one subgraph, the remaining
+        // nodes are each a component on their own.
+        let mut subgraph_component = TiVec::<GraphIdx, Option<u32>>::default();
+        subgraph_component.resize(self.subgraphs.len(), None);
+        let mut next = 0;
+        let mut fresh = || {
+            next += 1;
+            next - 1
+        };
+        self.raw
+            .graph
+            .node_weights()
+            .map(|node| match node.subgraph {
+                Some((sg, _)) => *subgraph_component[sg].get_or_insert_with(&mut fresh),
+                None => fresh(),
+            })
+            .collect()
+    }
+
+    /// Follows the instantiations below `start`, at each step moving to the
+    /// next instantiation with the smallest `fwd_depth.min` which is strictly
+    /// greater than that of the current node (ties go to the lowest index).
+    /// Terms and equalities in between are skipped over, and so are the side
+    /// branches which do not get any deeper. The returned chain starts with
+    /// `start`.
+    pub fn monotone_depth_chain(&self, start: RawNodeIndex) -> Vec<RawNodeIndex> {
+        let mut chain = vec![start];
+        let mut curr = start;
+        loop {
+            let depth = self.raw[curr].fwd_depth.min;
+            let mut next: Option<(u32, RawNodeIndex)> = None;
+            let mut seen = FxHashSet::default();
+            let mut stack = vec![curr];
+            while let Some(node) = stack.pop() {
+                let children = self
+                    .raw
+                    .graph
+                    .neighbors_directed(node.0, Direction::Outgoing);
+                for child in children.map(RawNodeIndex) {
+                    let child_node = &self.raw[child];
+                    if child_node.kind().inst().is_some() && !child_node.disabled() {
+                        let child_depth = child_node.fwd_depth.min;
+                        let candidate = (child_depth, child);
+                        if child_depth > depth && next.filter(|&n| n <= candidate).is_none() {
+                            next = Some(candidate);
+                        }
+                    } else if seen.insert(child) {
+                        stack.push(child);
+                    }
+                }
+            }
+            let Some((_, next)) = next else {
+                return chain;
+            };
+            chain.push(next);
+            curr = next;
+        }
+    }
+
+    /// The instantiations in the `k`-core of the graph of instantiations: the
+    /// largest set where each instantiation is adjacent to at least `k`
+    /// others in the set. Two instantiations are adjacent if one depends on a
+    /// term or equality produced by the other (in either direction). Found by
+    /// repeatedly removing instantiations with fewer than `k` neighbours left.
+    /// Returned in index order.
+    pub fn k_core(&self, k: usize) -> Vec<RawNodeIndex> {
+        let mut neighbours = FxHashMap::<InstIdx, FxHashSet<InstIdx>>::default();
+        for node in self.raw.graph.node_weights() {
+            let Some(inst) = node.kind().inst() else {
+                continue;
+            };
+            neighbours.entry(inst).or_default();
+            for parent in self.inst_parents(inst) {
+                if parent != inst {
+                    neighbours.entry(inst).or_default().insert(parent);
+                    neighbours.entry(parent).or_default().insert(inst);
+                }
+            }
+        }
+        let mut removed = FxHashSet::default();
+        let mut stack: Vec<_> = neighbours
+            .iter()
+            .filter(|(_, n)| n.len() < k)
+            .map(|(&i, _)| i)
+            .collect();
+        let mut degree: FxHashMap<_, _> = neighbours.iter().map(|(&i, n)| (i, n.len())).collect();
+        while let Some(inst) = stack.pop() {
+            if !removed.insert(inst) {
+                continue;
+            }
+            for &next in &neighbours[&inst] {
+                let d = degree.get_mut(&next).unwrap();
+                *d -= 1;
+                if *d < k && !removed.contains(&next) {
+                    stack.push(next);
+                }
+            }
+        }
+        let mut core: Vec<_> = neighbours
+            .into_keys()
+            .filter(|i| !removed.contains(i))
+            .map(|i| i.index(&self.raw))
+            .collect();
+        core.sort_unstable();
+        core
+    }
+
+    /// An approximation of the betweenness centrality of each node: roughly
+    /// how many of the shortest dependency paths between two other nodes
+    /// pass through it. Computing this exactly requires a search from every
+    /// node, so instead only `samples` source nodes, spread evenly over the
+    /// node indices, are searched from and the result is scaled up
+    /// accordingly. The values depend on `samples` and are only comparable
+    /// between calls with the same number of samples; they are exact once
+    /// `samples` reaches the number of nodes.
+    pub fn path_centrality(&self, samples: usize) -> TiVec<RawNodeIndex, f32> {
+        let graph = &*self.raw.graph;
+        let n = graph.node_count();
+        let samples = samples.min(n);
+        let mut centrality = vec![0.0_f64; n];
+        // Brandes' algorithm, restricted to the sampled sources.
+        let mut paths = vec![0.0_f64; n];
+        let mut dist = vec![u32::MAX; n];
+        let mut dependency = vec![0.0_f64; n];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+        for sample in 0..samples {
+            for &v in &order {
+                paths[v] = 0.0;
+                dist[v] = u32::MAX;
+                dependency[v] = 0.0;
+            }
+            order.clear();
+            let source = sample * n / samples;
+            paths[source] = 1.0;
+            dist[source] = 0;
+            queue.push_back(source);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let children = graph.neighbors_directed(NodeIndex::new(v), Direction::Outgoing);
+                for w in children.map(|w| w.index()) {
+                    if dist[w] == u32::MAX {
+                        dist[w] = dist[v] + 1;
+                        queue.push_back(w);
+                    }
+                    if dist[w] == dist[v] + 1 {
+                        paths[w] += paths[v];
+                    }
+                }
+            }
+            for &w in order.iter().rev() {
+                let parents = graph.neighbors_directed(NodeIndex::new(w), Direction::Incoming);
+                for v in parents.map(|v| v.index()) {
+                    if dist[v] != u32::MAX && dist[v] + 1 == dist[w] {
+                        dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+                    }
+                }
+                if w != source {
+                    centrality[w] += dependency[w];
+                }
+            }
+        }
+        let scale = if samples == 0 {
+            0.0
+        } else {
+            n as f64 / samples as f64
+        };
+        centrality.into_iter().map(|c| (c * scale) as f32).collect()
+    }
+
+    /// The enodes of the literals in the `[conflict]` lines of the log and
+    /// every node from which one of them can be reached, i.e. the
+    /// instantiations, terms and equalities which (indirectly) fed into a
+    /// conflict clause. Returned in index order.
+    pub fn conflict_relevant_nodes(&self, parser: &Z3Parser) -> Vec<RawNodeIndex> {
+        let mut stack: Vec<_> = parser
+            .conflicts()
+            .iter()
+            .flat_map(|lits| lits.iter().filter_map(|lit| lit.enode))
+            .map(|enode| enode.index(&self.raw))
+            .collect();
+        let mut relevant = FxHashSet::default();
+        while let Some(node) = stack.pop() {
+            if relevant.insert(node) {
+                let parents = self
+                    .raw
+                    .graph
+                    .neighbors_directed(node.0, Direction::Incoming);
+                stack.extend(parents.map(RawNodeIndex));
+            }
+        }
+        let mut relevant: Vec<_> = relevant.into_iter().collect();
+        relevant.sort_unstable();
+        relevant
+    }
+
+    /// The node created by line `line` of the log, i.e. the instantiation of
+    /// an `[instance]` line or the enode of an `[attach-enode]` line. `None`
+    /// for any other line.
+    pub fn node_at_line(&self, parser: &Z3Parser, line: usize) -> Option<RawNodeIndex> {
+        self.raw
+            .graph
+            .node_indices()
+            .find(|&n| self.raw.graph[n].kind().line_no(parser) == Some(line))
+            .map(RawNodeIndex)
+    }
+
+    /// The graph as a forest of trees if no node has more than one parent,
+    /// otherwise `None`. Disabled nodes are skipped over, i.e. a node's
+    /// parents are its closest enabled ancestors. The roots and the children
+    /// of each node are in index order.
+    pub fn as_forest(&self) -> Option<Vec<TreeNode>> {
+        let neighbors = |node, dir| {
+            let mut nodes = self.raw.neighbors_directed(node, dir);
+            nodes.sort_unstable();
+            nodes.dedup();
+            nodes
+        };
+        let mut roots = Vec::new();
+        for node in self.raw.node_indices() {
+            if self.raw[node].disabled() {
+                continue;
+            }
+            match neighbors(node, Direction::Incoming).len() {
+                0 => roots.push(node),
+                1 => (),
+                _ => return None,
+            }
+        }
+        // Build the trees bottom up rather than recursively since chains of
+        // instantiations can be very deep.
+        let mut order = Vec::new();
+        let mut stack = roots.clone();
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            stack.extend(neighbors(node, Direction::Outgoing));
+        }
+        let mut built = FxHashMap::default();
+        for &node in order.iter().rev() {
+            let children = neighbors(node, Direction::Outgoing)
+                .into_iter()
+                .map(|child| built.remove(&child).unwrap())
+                .collect();
+            built.insert(node, TreeNode { node, children });
+        }
+        Some(
+            roots
+                .into_iter()
+                .map(|root| built.remove(&root).unwrap())
+                .collect(),
+        )
+    }
+
     pub fn visible_unchanged(&self, old: &VisibleInstGraph) -> bool {
         self.raw.stats.generation == old.generation
     }
 }
 
+/// A node of the forest returned by [`InstGraph::as_forest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub node: RawNodeIndex,
+    pub children: Vec<TreeNode>,
+}
+
 #[macro_export]
 macro_rules! graph_idx {
     ($mod_name:ident, $node:ident, $edge:ident, $inner:ident) => {
@@ -72,11 +358,24 @@ macro_rules! graph_idx {
                     Self::from(usize::MAX - 1)
                 }
             }
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             #[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd, Eq, Ord, Hash)]
             pub struct $node(pub petgraph::graph::NodeIndex<$inner>);
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             #[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd, Eq, Ord, Hash)]
             pub struct $edge(pub petgraph::graph::EdgeIndex<$inner>);
 
+            impl From<usize> for $node {
+                fn from(value: usize) -> Self {
+                    Self(petgraph::graph::NodeIndex::new(value))
+                }
+            }
+            impl From<$node> for usize {
+                fn from(value: $node) -> Self {
+                    value.0.index()
+                }
+            }
+
             #[cfg(feature = "mem_dbg")]
             impl MemDbgImpl for $node {}
             #[cfg(feature = "mem_dbg")]
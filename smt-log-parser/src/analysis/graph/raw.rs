@@ -8,7 +8,7 @@ use fxhash::FxHashSet;
 use mem_dbg::{MemDbg, MemSize};
 use petgraph::{
     graph::NodeIndex,
-    visit::{Reversed, Visitable},
+    visit::{Reversed, Topo, Visitable},
     Direction::{self, Incoming, Outgoing},
 };
 
@@ -39,6 +39,12 @@ pub struct RawInstGraph {
 
 impl RawInstGraph {
     pub fn new(parser: &Z3Parser) -> Result<Self> {
+        Self::new_with_progress(parser, |_| ())
+    }
+
+    /// Reports progress from 0 to 1, adding the nodes takes up the first
+    /// third and the different kinds of edges the rest.
+    pub fn new_with_progress(parser: &Z3Parser, mut progress: impl FnMut(f32)) -> Result<Self> {
         let total_nodes = parser.insts.insts.len()
             + parser.egraph.enodes.len()
             + parser.egraph.equalities.given.len()
@@ -89,6 +95,7 @@ impl RawInstGraph {
             inst_idx,
             stats,
         };
+        progress(1.0 / 3.0);
 
         // Add instantiation blamed and yield edges
         for (idx, inst) in parser.insts.insts.iter_enumerated() {
@@ -114,6 +121,8 @@ impl RawInstGraph {
             }
         }
 
+        progress(0.6);
+
         // Add given equality created edges
         for (idx, eq) in parser.egraph.equalities.given.iter_enumerated() {
             match eq {
@@ -135,6 +144,8 @@ impl RawInstGraph {
             }
         }
 
+        progress(0.8);
+
         // Add transitive equality created edges
         for (idx, eq) in parser.egraph.equalities.transitive.iter_enumerated() {
             let all = eq.all(true);
@@ -157,6 +168,7 @@ impl RawInstGraph {
                 }
             }
         }
+        progress(1.0);
 
         Ok(self_)
     }
@@ -249,6 +261,75 @@ impl RawInstGraph {
     pub fn node_indices(&self) -> impl Iterator<Item = RawNodeIndex> {
         self.graph.node_indices().map(RawNodeIndex)
     }
+    /// Dims exactly the nodes in `dimmed`, undimming all others. Dimmed nodes
+    /// keep their visibility, they are only drawn faded out (e.g. to focus on
+    /// part of the graph without changing its layout). Bumps the generation
+    /// if anything changed, so that the graph is drawn again.
+    pub fn set_dimmed(&mut self, dimmed: FxHashSet<RawNodeIndex>) {
+        let mut changed = false;
+        for idx in self.graph.node_indices() {
+            let dim = dimmed.contains(&RawNodeIndex(idx));
+            let node = &mut self.graph[idx];
+            changed |= node.dimmed != dim;
+            node.dimmed = dim;
+        }
+        if changed {
+            self.stats.generation = self.stats.generation.wrapping_add(1);
+        }
+    }
+    /// The number of `GivenEquality` and `TransEquality` nodes, whatever
+    /// their state.
+    pub fn equality_nodes(&self) -> usize {
+        self.inst_idx.0.index() - self.eq_trans_idx.0.index() + self.eq_given_idx.len()
+    }
+
+    /// The height of the subtree below each node: the number of edges on the
+    /// longest path from the node down to a leaf. Leaves have height 0.
+    pub fn subtree_heights(&self) -> TiVec<RawNodeIndex, u32> {
+        let mut order = Vec::with_capacity(self.graph.node_count());
+        let mut topo = Topo::new(&*self.graph);
+        while let Some(node) = topo.next(&*self.graph) {
+            order.push(node);
+        }
+        let mut heights: TiVec<RawNodeIndex, u32> = TiVec::default();
+        heights.resize(self.graph.node_count(), 0);
+        for &node in order.iter().rev() {
+            heights[RawNodeIndex(node)] = self
+                .graph
+                .neighbors_directed(node, Outgoing)
+                .map(|child| heights[RawNodeIndex(child)] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        heights
+    }
+
+    /// Up to `n` nodes closest to `start` (which comes first, at distance 0),
+    /// found by a breadth-first search ignoring edge directions. Distances
+    /// are in hops between enabled nodes, disabled nodes are passed through
+    /// as in [`RawInstGraph::neighbors_directed`].
+    pub fn nearest(&self, start: RawNodeIndex, n: usize) -> Vec<(RawNodeIndex, u32)> {
+        let mut nearest = Vec::with_capacity(n.min(self.graph.node_count()));
+        let mut seen = FxHashSet::default();
+        seen.insert(start);
+        let mut queue = std::collections::VecDeque::from([(start, 0)]);
+        while let Some((node, dist)) = queue.pop_front() {
+            if nearest.len() >= n {
+                break;
+            }
+            nearest.push((node, dist));
+            let incoming = self.neighbors_directed(node, Incoming);
+            for next in incoming
+                .into_iter()
+                .chain(self.neighbors_directed(node, Outgoing))
+            {
+                if seen.insert(next) {
+                    queue.push_back((next, dist + 1));
+                }
+            }
+        }
+        nearest
+    }
 }
 
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
@@ -285,6 +366,10 @@ impl GraphStats {
 #[derive(Debug, Clone)]
 pub struct Node {
     state: NodeState,
+    /// Drawn faded out. Unlike hiding, dimming does not change which nodes
+    /// are in the visible graph, so the layout stays the same. See
+    /// [`RawInstGraph::set_dimmed`].
+    dimmed: bool,
     pub cost: f64,
     pub fwd_depth: Depth,
     pub bwd_depth: Depth,
@@ -320,6 +405,7 @@ impl Node {
     fn new(kind: NodeKind) -> Self {
         Self {
             state: NodeState::Hidden,
+            dimmed: false,
             cost: 0.0,
             fwd_depth: Depth::default(),
             bwd_depth: Depth::default(),
@@ -347,6 +433,20 @@ impl Node {
     pub fn visible(&self) -> bool {
         matches!(self.state, NodeState::Visible)
     }
+    /// Whether the node is part of a visible graph, equality nodes are left
+    /// out of it if `inline_equalities` is set, see
+    /// [`InstGraph::to_visible_inline_equalities`](super::InstGraph::to_visible_inline_equalities).
+    pub fn shown(&self, inline_equalities: bool) -> bool {
+        self.visible()
+            && !(inline_equalities
+                && matches!(
+                    self.kind,
+                    NodeKind::GivenEquality(..) | NodeKind::TransEquality(_)
+                ))
+    }
+    pub fn dimmed(&self) -> bool {
+        self.dimmed
+    }
     pub fn hidden_inst(&self) -> bool {
         matches!(
             (self.state, self.kind),
@@ -427,6 +527,16 @@ impl NodeKind {
             _ => None,
         }
     }
+    /// The line of the log which created the node: the `[instance]` of an
+    /// instantiation or the `[attach-enode]` of an enode. Equalities do not
+    /// record a line.
+    pub fn line_no(&self, parser: &Z3Parser) -> Option<usize> {
+        match self {
+            Self::Instantiation(inst) => Some(parser[*inst].line_no),
+            Self::ENode(enode) => parser[*enode].line_no,
+            Self::GivenEquality(..) | Self::TransEquality(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
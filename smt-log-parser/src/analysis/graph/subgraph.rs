@@ -1,4 +1,6 @@
-use fxhash::FxHashSet;
+use std::collections::VecDeque;
+
+use fxhash::{FxHashMap, FxHashSet};
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::{MemDbg, MemSize};
 use petgraph::{
@@ -11,6 +13,10 @@ use roaring::RoaringBitmap;
 
 use super::{raw::RawIx, InstGraph, RawNodeIndex};
 
+/// A weakly connected component of the instantiation graph (with at least one
+/// edge), along with its precomputed reachability. Nodes are identified by
+/// their `u32` position in [`Subgraph::nodes`], the same position is stored in
+/// the second field of [`Node::subgraph`](super::raw::Node::subgraph).
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
 #[derive(Debug)]
 pub struct Subgraph {
@@ -26,6 +32,12 @@ pub struct VisitBox<D: VisitMap<NodeIndex<RawIx>>> {
 }
 
 impl Subgraph {
+    /// The nodes of the subgraph in topological order, indexed by their
+    /// position in the subgraph.
+    pub fn nodes(&self) -> &[RawNodeIndex] {
+        &self.nodes
+    }
+
     pub fn new<N, E, D: VisitMap<NodeIndex<RawIx>>>(
         node: RawNodeIndex,
         graph: &mut DiGraph<N, E, RawIx>,
@@ -141,17 +153,22 @@ impl Subgraph {
     }
 }
 
+/// Reachability between the nodes of a [`Subgraph`], all nodes are given as
+/// their position in the subgraph. Every node reaches itself.
 #[derive(Debug)]
 pub struct TransitiveClosure(Vec<RoaringBitmap>);
 impl TransitiveClosure {
+    /// Is there a path from `from` to `to`? Constant time.
     pub fn in_transitive_closure(&self, from: u32, to: u32) -> bool {
         // self.reach_fwd.contains(from * self.nodes.len() as u32 + to)
         self.0[from as usize].contains(to)
     }
+    /// All nodes reachable from `from`, in increasing order.
     pub fn reachable_from(&self, from: u32) -> impl Iterator<Item = u32> + '_ {
         // (0..self.nodes.len() as u32).filter(move |&to| self.in_transitive_closure(from, to))
         self.0[from as usize].iter()
     }
+    /// The union of the nodes reachable from any of the nodes in `from`.
     pub fn reachable_from_many(&self, from: impl Iterator<Item = u32>) -> RoaringBitmap {
         let mut reachable = RoaringBitmap::new();
         for from in from {
@@ -167,10 +184,22 @@ impl TransitiveClosure {
 }
 
 impl InstGraph {
+    /// The subgraph containing `idx`, `None` if the node has no edges. Use
+    /// with [`Node::subgraph`](super::raw::Node::subgraph) to find the
+    /// position of `idx` within the subgraph for reachability queries.
+    pub fn subgraph_of(&self, idx: RawNodeIndex) -> Option<&Subgraph> {
+        let (sg, _) = self.raw[idx].subgraph?;
+        Some(&self.subgraphs[sg])
+    }
+
+    /// The nodes on paths from `from` to `to` which are not part of the
+    /// visible graph, see [`Node::shown`](super::raw::Node::shown) for
+    /// `inline_equalities`.
     pub fn non_visible_paths_between(
         &self,
         from: RawNodeIndex,
         to: RawNodeIndex,
+        inline_equalities: bool,
     ) -> Option<(FxHashSet<RawNodeIndex>, Option<Vec<RawNodeIndex>>)> {
         if from == to {
             return Some(([to].into_iter().collect(), Some(vec![to])));
@@ -186,7 +215,7 @@ impl InstGraph {
 
         let filtered = NodeFiltered::from_fn(&*self.raw.graph, |n| {
             let node = &self.raw.graph[n];
-            !node.visible()
+            !node.shown(inline_equalities)
                 && node.subgraph.is_some_and(|(subgraph, idx)| {
                     subgraph == from_subgraph
                         && self.subgraphs[subgraph]
@@ -228,6 +257,58 @@ impl InstGraph {
         }
         Some((paths_between, simple_path))
     }
+
+    /// Returns the `terminals` along with enough additional nodes to connect
+    /// them, ignoring edge directions. Terminals in different weakly connected
+    /// components cannot be connected and are returned on their own.
+    ///
+    /// This is a heuristic and does not compute a minimal Steiner tree: the
+    /// terminals are greedily attached one at a time, each time via a
+    /// shortest path from the nodes collected so far to the closest remaining
+    /// terminal. The result is in the order nodes were added.
+    pub fn steiner_like_subgraph(&self, terminals: &[RawNodeIndex]) -> Vec<RawNodeIndex> {
+        let mut remaining: FxHashSet<_> = terminals.iter().copied().collect();
+        let mut tree = Vec::new();
+        let mut in_tree = FxHashSet::default();
+        let mut parent = FxHashMap::default();
+        let mut queue = VecDeque::new();
+        while let Some(&root) = terminals.iter().find(|t| remaining.contains(*t)) {
+            // Start a new component from the first unconnected terminal.
+            remaining.remove(&root);
+            in_tree.insert(root);
+            tree.push(root);
+            let component_start = tree.len() - 1;
+            loop {
+                parent.clear();
+                queue.clear();
+                queue.extend(tree[component_start..].iter().copied());
+                let mut found = None;
+                while let Some(node) = queue.pop_front() {
+                    if remaining.contains(&node) {
+                        found = Some(node);
+                        break;
+                    }
+                    for next in self.raw.graph.neighbors_undirected(node.0) {
+                        let next = RawNodeIndex(next);
+                        if in_tree.contains(&next) || parent.contains_key(&next) {
+                            continue;
+                        }
+                        parent.insert(next, node);
+                        queue.push_back(next);
+                    }
+                }
+                let Some(mut node) = found else {
+                    break;
+                };
+                while in_tree.insert(node) {
+                    tree.push(node);
+                    remaining.remove(&node);
+                    node = parent[&node];
+                }
+            }
+        }
+        tree
+    }
 }
 
 // Graph wrapper for Topo walk
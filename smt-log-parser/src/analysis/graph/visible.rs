@@ -9,13 +9,13 @@ use petgraph::{
 
 use crate::{
     graph_idx,
-    items::{ENodeIdx, EqGivenIdx},
-    NonMaxU32,
+    items::{ENodeIdx, EqGivenIdx, EqTransIdx, InstIdx},
+    NonMaxU32, Z3Parser,
 };
 
 use super::{
     analysis::matching_loop::MIN_MATCHING_LOOP_LENGTH,
-    raw::{EdgeKind, Node, NodeKind},
+    raw::{EdgeKind, IndexesInstGraph, Node, NodeKind},
     InstGraph, RawEdgeIndex, RawNodeIndex,
 };
 
@@ -25,11 +25,28 @@ graph_idx!(visible_idx, VisibleNodeIndex, VisibleEdgeIndex, VisibleIx);
 pub struct VisibleInstGraph {
     pub graph: DiGraph<VisibleNode, VisibleEdge, VisibleIx>,
     reverse: FxHashMap<RawNodeIndex, VisibleNodeIndex>,
+    /// Indirect edges which were dropped in favour of the kept edge between
+    /// the same two visible nodes, see [`VisibleInstGraph::merged_paths`].
+    merged: FxHashMap<VisibleEdgeIndex, Vec<VisibleEdge>>,
+    edges: EdgeVisibility,
+    inline_equalities: bool,
     pub generation: u32,
 }
 
 impl InstGraph {
     pub fn to_visible(&self) -> VisibleInstGraph {
+        self.to_visible_with(false)
+    }
+
+    /// Same as [`to_visible`](Self::to_visible) but without any equality
+    /// nodes, edges between the remaining nodes pass through them instead.
+    /// The equality which such an edge relies on is then available from
+    /// [`VisibleEdgeKind::given_equality`].
+    pub fn to_visible_inline_equalities(&self) -> VisibleInstGraph {
+        self.to_visible_with(true)
+    }
+
+    fn to_visible_with(&self, inline_equalities: bool) -> VisibleInstGraph {
         // debug_assert_eq!(self.hidden as usize, self.graph.node_weights().filter(|n| n.hidden).count());
 
         // Code copied from `DiGraph::filter_map`, but that function does not
@@ -39,7 +56,7 @@ impl InstGraph {
         // mapping from old node index to new node index, end represents removed.
         let mut node_index_map = vec![NodeIndex::end(); self.raw.graph.node_count()];
         let node_map = |idx, node: &Node| {
-            node.visible().then(|| VisibleNode {
+            node.shown(inline_equalities).then(|| VisibleNode {
                 idx,
                 hidden_parents: self
                     .raw
@@ -54,6 +71,7 @@ impl InstGraph {
                     .filter(|n| self.raw.graph[n.0].hidden())
                     .count() as u32,
                 max_depth: 0,
+                dimmed: node.dimmed(),
             })
         };
         for (i, node) in self.raw.graph.node_weights().enumerate() {
@@ -81,9 +99,13 @@ impl InstGraph {
         let mut self_ = VisibleInstGraph {
             graph,
             reverse,
+            merged: FxHashMap::default(),
+            edges: EdgeVisibility::Both,
+            inline_equalities,
             generation: self.raw.stats.generation,
         };
         self_.reconnect(self);
+        self_.merge_indirect();
         self_
     }
 
@@ -114,6 +136,7 @@ impl InstGraph {
                     .filter(|&n| self.raw[*n].hidden())
                     .count() as u32,
                 max_depth: 0,
+                dimmed: node.dimmed(),
             })
         };
         for (i, node) in self.raw.graph.node_weights().enumerate() {
@@ -147,6 +170,9 @@ impl InstGraph {
         let mut self_ = VisibleInstGraph {
             graph,
             reverse,
+            merged: FxHashMap::default(),
+            edges: EdgeVisibility::Both,
+            inline_equalities: false,
             generation: self.raw.stats.generation,
         };
         self_.reconnect_simplified(self);
@@ -159,6 +185,23 @@ impl VisibleInstGraph {
         self.reverse.contains_key(&i_idx)
     }
 
+    /// Makes the hidden nodes along the indirect edge `e` visible in `igraph`
+    /// and then rebuilds this graph from it. Unlike showing all neighbours of
+    /// a node, this only reveals the nodes which the edge was summarising
+    /// (including those of its [merged paths](Self::merged_paths)). Has no
+    /// effect for direct edges.
+    pub fn expand_indirect(&mut self, e: VisibleEdgeIndex, igraph: &mut InstGraph) {
+        let mut nodes = self[e].indirect_nodes(igraph);
+        for merged in self.merged_paths(e) {
+            nodes.extend(merged.indirect_nodes(igraph));
+        }
+        if nodes.is_empty() {
+            return;
+        }
+        igraph.raw.set_visibility_many(false, nodes.into_iter());
+        *self = igraph.to_visible_with(self.inline_equalities);
+    }
+
     fn reconnect(&mut self, igraph: &InstGraph) {
         // Look for tuples of 4 indices:
         //  - `from`: a visible node
@@ -180,10 +223,11 @@ impl VisibleInstGraph {
             };
             let sg = &igraph.subgraphs[sg];
             // Found a potential `from` node, look for `from_child` nodes.
+            let shown = |n: NodeIndex<_>| igraph.raw.graph[n].shown(self.inline_equalities);
             for i_from_edge in igraph.raw.graph.edges(i_from.0) {
                 // Edge between `from` -> `from_child`
                 let i_from_child = i_from_edge.target();
-                if igraph.raw.graph[i_from_child].visible() {
+                if shown(i_from_child) {
                     continue;
                 }
                 let s_from_child = igraph.raw.graph[i_from_child].subgraph.unwrap().1;
@@ -191,7 +235,7 @@ impl VisibleInstGraph {
                 let visible_reachable = || {
                     sg.reach_fwd
                         .reachable_from(s_from_child)
-                        .filter(|&s| igraph.raw.graph[sg.nodes[s as usize].0].visible())
+                        .filter(|&s| shown(sg.nodes[s as usize].0))
                 };
                 // All nodes reachable from "any visible node reachable from `s_from_child`".
                 let fwd_reachable = sg.reach_fwd.reachable_from_many(visible_reachable());
@@ -209,7 +253,7 @@ impl VisibleInstGraph {
                     // Found a potential `to_parent` node, look for `to` nodes.
                     for i_to_edge in igraph.raw.graph.edges(i_to_parent) {
                         let i_to = RawNodeIndex(i_to_edge.target());
-                        if !igraph.raw.graph[i_to.0].visible() {
+                        if !shown(i_to.0) {
                             continue;
                         }
                         let v_to = self.reverse[&i_to];
@@ -219,6 +263,7 @@ impl VisibleInstGraph {
                             VisibleEdge::Indirect(
                                 RawEdgeIndex(i_from_edge.id()),
                                 RawEdgeIndex(i_to_edge.id()),
+                                self.inline_equalities,
                             ),
                         );
                     }
@@ -227,6 +272,74 @@ impl VisibleInstGraph {
         }
     }
 
+    /// Hidden paths from one visible node often share their first raw edge
+    /// and only diverge towards the end, leading `reconnect` to add several
+    /// indirect edges between the same two visible nodes. Keeps only the
+    /// first of these and remembers the rest in `merged`.
+    fn merge_indirect(&mut self) {
+        let edges = self.graph.raw_edges().to_vec();
+        self.graph.clear_edges();
+        let mut kept = FxHashMap::default();
+        for edge in edges {
+            let (from, to) = (edge.source(), edge.target());
+            if !matches!(edge.weight, VisibleEdge::Indirect(..)) {
+                self.graph.add_edge(from, to, edge.weight);
+                continue;
+            }
+            if let Some(&e) = kept.get(&(from, to)) {
+                self.merged.entry(e).or_default().push(edge.weight);
+                continue;
+            }
+            let e = VisibleEdgeIndex(self.graph.add_edge(from, to, edge.weight));
+            kept.insert((from, to), e);
+        }
+    }
+
+    /// The indirect edges which were merged into `e` since they connect the
+    /// same two visible nodes through a different hidden path. Empty for
+    /// direct edges and for indirect edges which summarise a single path.
+    pub fn merged_paths(&self, e: VisibleEdgeIndex) -> &[VisibleEdge] {
+        self.merged.get(&e).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Removes the edges which are not of the kinds selected by `edges`,
+    /// leaving the nodes as they are. Edges can only be removed, so
+    /// switching back to [`EdgeVisibility::Both`] requires recalculating the
+    /// graph with [`InstGraph::to_visible`].
+    pub fn retain_edges(&mut self, edges: EdgeVisibility) {
+        self.edges = edges;
+        if edges == EdgeVisibility::Both {
+            return;
+        }
+        let old_edges = self.graph.raw_edges().to_vec();
+        self.graph.clear_edges();
+        let mut merged = std::mem::take(&mut self.merged);
+        for (old, edge) in old_edges.into_iter().enumerate() {
+            if !edges.keeps(&edge.weight) {
+                continue;
+            }
+            let new = self
+                .graph
+                .add_edge(edge.source(), edge.target(), edge.weight);
+            let old = VisibleEdgeIndex(EdgeIndex::new(old));
+            if let Some(paths) = merged.remove(&old) {
+                self.merged.insert(VisibleEdgeIndex(new), paths);
+            }
+        }
+    }
+
+    /// The kinds of edges kept by the last call to
+    /// [`retain_edges`](Self::retain_edges).
+    pub fn edge_visibility(&self) -> EdgeVisibility {
+        self.edges
+    }
+
+    /// Whether this graph was built by
+    /// [`InstGraph::to_visible_inline_equalities`].
+    pub fn inline_equalities(&self) -> bool {
+        self.inline_equalities
+    }
+
     fn reconnect_simplified(&mut self, igraph: &InstGraph) {
         // remember all direct edges (will be added to the graph in the end)
         let direct_edges = self.graph.raw_edges().to_vec();
@@ -272,7 +385,11 @@ impl VisibleInstGraph {
                         self.graph.update_edge(
                             u,
                             v,
-                            VisibleEdge::Indirect(RawEdgeIndex::default(), RawEdgeIndex::default()),
+                            VisibleEdge::Indirect(
+                                RawEdgeIndex::default(),
+                                RawEdgeIndex::default(),
+                                false,
+                            ),
                         );
                     }
                 }
@@ -307,7 +424,7 @@ impl VisibleInstGraph {
                 self.graph.add_edge(
                     source,
                     target,
-                    VisibleEdge::Indirect(RawEdgeIndex::default(), RawEdgeIndex::default()),
+                    VisibleEdge::Indirect(RawEdgeIndex::default(), RawEdgeIndex::default(), false),
                 );
             }
         }
@@ -387,6 +504,28 @@ impl VisibleInstGraph {
         matching_loop_nodes
     }
 
+    /// The hidden nodes which an indirect edge passes through, in order from
+    /// the source to the target of the edge. Empty for direct edges. When
+    /// there are several hidden paths between the endpoints one of them is
+    /// picked.
+    pub fn indirect_path(&self, e: VisibleEdgeIndex, igraph: &InstGraph) -> Vec<RawNodeIndex> {
+        self[e]
+            .indirect_paths(igraph)
+            .and_then(|(_, path)| path)
+            .unwrap_or_default()
+    }
+
+    /// The visible nodes in topological order, i.e. every node comes after
+    /// all of its parents. Hiding nodes and adding indirect edges between the
+    /// remaining ones keeps the graph acyclic, so this should never fail.
+    /// If a cycle is found nonetheless, returns `Err` with one of the nodes
+    /// on the cycle rather than a partial order.
+    pub fn topo_order(&self) -> Result<Vec<VisibleNodeIndex>, VisibleNodeIndex> {
+        petgraph::algo::toposort(&self.graph, None)
+            .map(|order| order.into_iter().map(VisibleNodeIndex).collect())
+            .map_err(|cycle| VisibleNodeIndex(cycle.node_id()))
+    }
+
     pub fn compute_longest_distances_from_roots(&mut self) {
         let mut topo = Topo::new(&self.graph);
         while let Some(nx) = topo.next(&self.graph) {
@@ -432,12 +571,39 @@ pub struct VisibleNode {
     pub hidden_parents: u32,
     pub hidden_children: u32,
     pub max_depth: usize,
+    /// See [`RawInstGraph::set_dimmed`](super::raw::RawInstGraph::set_dimmed).
+    pub dimmed: bool,
+}
+
+/// Which kinds of edges a [`VisibleInstGraph`] should keep, see
+/// [`VisibleInstGraph::retain_edges`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EdgeVisibility {
+    DirectOnly,
+    IndirectOnly,
+    #[default]
+    Both,
+}
+
+impl EdgeVisibility {
+    pub fn keeps(self, edge: &VisibleEdge) -> bool {
+        match (self, edge) {
+            (EdgeVisibility::Both, _)
+            | (EdgeVisibility::DirectOnly, VisibleEdge::Direct(_))
+            | (EdgeVisibility::IndirectOnly, VisibleEdge::Indirect(..)) => true,
+            (EdgeVisibility::DirectOnly, VisibleEdge::Indirect(..))
+            | (EdgeVisibility::IndirectOnly, VisibleEdge::Direct(_)) => false,
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub enum VisibleEdge {
     Direct(RawEdgeIndex),
-    Indirect(RawEdgeIndex, RawEdgeIndex),
+    /// An edge through nodes which are not part of the visible graph, from
+    /// the first to the last raw edge of the path. The flag is whether the
+    /// graph was built with equalities inline, see [`Node::shown`].
+    Indirect(RawEdgeIndex, RawEdgeIndex, bool),
 }
 
 impl std::fmt::Debug for VisibleEdge {
@@ -450,21 +616,25 @@ impl std::fmt::Debug for VisibleEdge {
 }
 
 impl VisibleEdge {
-    fn indirect_nodes<'a>(&'a self, graph: &'a InstGraph) -> FxHashSet<RawNodeIndex> {
-        match self {
-            VisibleEdge::Direct(_) => FxHashSet::default(),
-            VisibleEdge::Indirect(from, to) =>
-            // graph.raw.graph.edge_endpoints(from.0).unwrap().1 --> graph.raw.graph.edge_endpoints(to.0).unwrap().0
-            {
-                graph
-                    .non_visible_paths_between(
-                        RawNodeIndex(graph.raw.graph.edge_endpoints(from.0).unwrap().1),
-                        RawNodeIndex(graph.raw.graph.edge_endpoints(to.0).unwrap().0),
-                    )
-                    .unwrap()
-                    .0
-            }
-        }
+    /// All nodes on the paths behind an indirect edge, together with one of
+    /// these paths in order, see [`InstGraph::non_visible_paths_between`].
+    /// `None` for direct edges or if the paths cannot be found.
+    fn indirect_paths(
+        &self,
+        graph: &InstGraph,
+    ) -> Option<(FxHashSet<RawNodeIndex>, Option<Vec<RawNodeIndex>>)> {
+        let VisibleEdge::Indirect(from, to, inline_equalities) = self else {
+            return None;
+        };
+        let raw = &graph.raw.graph;
+        let (_, first) = raw.edge_endpoints(from.0)?;
+        let (last, _) = raw.edge_endpoints(to.0)?;
+        graph.non_visible_paths_between(RawNodeIndex(first), RawNodeIndex(last), *inline_equalities)
+    }
+    fn indirect_nodes(&self, graph: &InstGraph) -> FxHashSet<RawNodeIndex> {
+        self.indirect_paths(graph)
+            .map(|(nodes, _)| nodes)
+            .unwrap_or_default()
     }
     pub fn is_indirect(&self, graph: &InstGraph) -> bool {
         self.indirect_nodes(graph)
@@ -474,14 +644,11 @@ impl VisibleEdge {
     pub fn kind(&self, graph: &InstGraph) -> VisibleEdgeKind {
         match self {
             VisibleEdge::Direct(e) => VisibleEdgeKind::Direct(*e, graph.raw.graph[e.0]),
-            VisibleEdge::Indirect(from, to) => {
+            VisibleEdge::Indirect(from, to, _) => {
                 // TODO: clean this up
-                let (all_between, non_visible_between) = graph
-                    .non_visible_paths_between(
-                        RawNodeIndex(graph.raw.graph.edge_endpoints(from.0).unwrap().1),
-                        RawNodeIndex(graph.raw.graph.edge_endpoints(to.0).unwrap().0),
-                    )
-                    .unwrap();
+                let Some((all_between, non_visible_between)) = self.indirect_paths(graph) else {
+                    return VisibleEdgeKind::Unknown(*from, *to);
+                };
                 if !non_visible_between
                     .as_ref()
                     .is_some_and(|non_visible_between| {
@@ -529,7 +696,10 @@ impl VisibleEdge {
                                 eq_order,
                             }
                         } else {
-                            VisibleEdgeKind::YieldEqOther(given_eq)
+                            VisibleEdgeKind::YieldEqOther {
+                                given_eq,
+                                trans: get_kind(2).unwrap().eq_trans().unwrap(),
+                            }
                         }
                     }
 
@@ -559,7 +729,10 @@ impl VisibleEdge {
                                 eq_order,
                             }
                         } else {
-                            VisibleEdgeKind::ENodeEqOther(given_eq)
+                            VisibleEdgeKind::ENodeEqOther {
+                                given_eq,
+                                trans: get_kind(1).unwrap().eq_trans().unwrap(),
+                            }
                         }
                     }
 
@@ -587,8 +760,12 @@ pub enum VisibleEdgeKind {
         trigger_term: u16,
         eq_order: u16,
     },
-    /// `Instantiation` -> `ENode` -> `GivenEquality` -> ...
-    YieldEqOther((EqGivenIdx, Option<NonMaxU32>)),
+    /// `Instantiation` -> `ENode` -> `GivenEquality` -> `TransEquality`
+    /// (multiple parents) -> `Instantiation`
+    YieldEqOther {
+        given_eq: (EqGivenIdx, Option<NonMaxU32>),
+        trans: EqTransIdx,
+    },
 
     /// `ENode` -> `GivenEquality` -> `TransEquality`
     ENodeEq((EqGivenIdx, Option<NonMaxU32>)),
@@ -598,13 +775,33 @@ pub enum VisibleEdgeKind {
         trigger_term: u16,
         eq_order: u16,
     },
-    /// `ENode` -> `GivenEquality` -> ...
-    ENodeEqOther((EqGivenIdx, Option<NonMaxU32>)),
+    /// `ENode` -> `GivenEquality` -> `TransEquality` (multiple parents) ->
+    /// `Instantiation`
+    ENodeEqOther {
+        given_eq: (EqGivenIdx, Option<NonMaxU32>),
+        trans: EqTransIdx,
+    },
 
     Unknown(RawEdgeIndex, RawEdgeIndex),
 }
 
 impl VisibleEdgeKind {
+    /// The given equality which a match relied on along this edge, if the
+    /// edge passes through one.
+    pub fn given_equality(&self) -> Option<(EqGivenIdx, Option<NonMaxU32>)> {
+        match self {
+            VisibleEdgeKind::YieldEq(given_eq)
+            | VisibleEdgeKind::YieldBlameEq { given_eq, .. }
+            | VisibleEdgeKind::YieldEqOther { given_eq, .. }
+            | VisibleEdgeKind::ENodeEq(given_eq)
+            | VisibleEdgeKind::ENodeBlameEq { given_eq, .. }
+            | VisibleEdgeKind::ENodeEqOther { given_eq, .. } => Some(*given_eq),
+            VisibleEdgeKind::Direct(..)
+            | VisibleEdgeKind::YieldBlame { .. }
+            | VisibleEdgeKind::Unknown(..) => None,
+        }
+    }
+
     pub fn blame(&self, graph: &InstGraph) -> NodeKind {
         use NodeKind::*;
         match self {
@@ -614,12 +811,55 @@ impl VisibleEdgeKind {
 
             VisibleEdgeKind::YieldEq(given_eq)
             | VisibleEdgeKind::YieldBlameEq { given_eq, .. }
-            | VisibleEdgeKind::YieldEqOther(given_eq)
+            | VisibleEdgeKind::YieldEqOther { given_eq, .. }
             | VisibleEdgeKind::ENodeEq(given_eq)
             | VisibleEdgeKind::ENodeBlameEq { given_eq, .. }
-            | VisibleEdgeKind::ENodeEqOther(given_eq) => GivenEquality(given_eq.0, given_eq.1),
+            | VisibleEdgeKind::ENodeEqOther { given_eq, .. } => {
+                GivenEquality(given_eq.0, given_eq.1)
+            }
 
             VisibleEdgeKind::YieldBlame { enode, .. } => ENode(*enode),
         }
     }
+
+    /// The number of steps in the transitive equality explanation which this
+    /// edge summarizes. Indirect edges through a simple equality are a single
+    /// step, the `*EqOther` edges count the segments of the hidden
+    /// `TransEquality`. Direct and unknown edges are `0`.
+    pub fn equality_steps(&self, graph: &InstGraph) -> usize {
+        match self {
+            VisibleEdgeKind::YieldEq(_)
+            | VisibleEdgeKind::YieldBlameEq { .. }
+            | VisibleEdgeKind::ENodeEq(_)
+            | VisibleEdgeKind::ENodeBlameEq { .. } => 1,
+            VisibleEdgeKind::YieldEqOther { trans, .. }
+            | VisibleEdgeKind::ENodeEqOther { trans, .. } => graph
+                .raw
+                .graph
+                .edges_directed(trans.index(&graph.raw).0, Direction::Incoming)
+                .count(),
+            VisibleEdgeKind::Direct(..)
+            | VisibleEdgeKind::YieldBlame { .. }
+            | VisibleEdgeKind::Unknown(..) => 0,
+        }
+    }
+
+    /// For edges which blame a term of the trigger that instantiation `inst`
+    /// (the target of the edge) matched, returns `(index, total)`: the
+    /// position of the blamed term within the trigger and the total number of
+    /// terms in the (possibly multi-pattern) trigger. Positions come from the
+    /// order of the blamed enodes rather than the terms of the trigger, so
+    /// this also works on a [`Z3Parser::new_summary`] parser.
+    pub fn trigger_position(&self, inst: InstIdx, parser: &Z3Parser) -> Option<(u16, u16)> {
+        let trigger_term = match self {
+            VisibleEdgeKind::Direct(_, EdgeKind::Blame { trigger_term })
+            | VisibleEdgeKind::Direct(_, EdgeKind::BlameEq { trigger_term, .. })
+            | VisibleEdgeKind::YieldBlame { trigger_term, .. }
+            | VisibleEdgeKind::YieldBlameEq { trigger_term, .. }
+            | VisibleEdgeKind::ENodeBlameEq { trigger_term, .. } => *trigger_term,
+            _ => return None,
+        };
+        let total = parser[parser[inst].match_].trigger_matches().count();
+        Some((trigger_term, total as u16))
+    }
 }
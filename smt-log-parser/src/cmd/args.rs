@@ -31,10 +31,29 @@ pub enum Commands {
         /// how many of the most instantiated axioms to print
         #[arg(short)]
         k: Option<usize>,
+        /// Search for matching loops and print a markdown summary of each
+        #[arg(long, default_value_t = false)]
+        loops: bool,
     },
     /// Tests the parser and analysis, printing out timing information
     Test {
         /// The paths to the smt log files
         logfiles: Vec<std::path::PathBuf>,
+
+        /// Cross-check the parsed data for consistency and print any
+        /// violations found
+        #[arg(long, default_value_t = false)]
+        validate: bool,
+
+        /// Print how long the parser spent in each phase
+        #[cfg(feature = "timing")]
+        #[arg(long, default_value_t = false)]
+        timing: bool,
+    },
+    #[cfg(feature = "serde")]
+    /// Prints one JSON object per instantiation, one per line
+    Jsonl {
+        /// The path to the smt log file
+        logfile: std::path::PathBuf,
     },
 }
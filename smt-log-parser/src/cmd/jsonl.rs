@@ -0,0 +1,20 @@
+use std::{io::BufWriter, path::PathBuf};
+
+use smt_log_parser::{LogParser, Z3Parser};
+
+pub fn run(logfile: PathBuf) -> Result<(), String> {
+    let path = std::path::Path::new(&logfile);
+    let filename = path
+        .file_name()
+        .map(|f| f.to_string_lossy())
+        .unwrap_or_default();
+
+    if !path.is_file() {
+        return Err(format!("path {filename} did not point to a file"));
+    }
+
+    let (_metadata, parser) = Z3Parser::from_file(path).map_err(|e| e.to_string())?;
+    let parser = parser.process_all().map_err(|e| e.to_string())?;
+    let stdout = BufWriter::new(std::io::stdout().lock());
+    parser.stream_jsonl(stdout).map_err(|e| e.to_string())
+}
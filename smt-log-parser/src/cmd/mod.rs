@@ -1,6 +1,8 @@
 mod args;
 #[cfg(feature = "analysis")]
 mod dependencies;
+#[cfg(feature = "serde")]
+mod jsonl;
 mod stats;
 mod test;
 
@@ -15,8 +17,17 @@ pub fn run() -> Result<(), String> {
             pretty_print,
         } => dependencies::run(logfile, depth, pretty_print)?,
         #[cfg(feature = "analysis")]
-        args::Commands::Stats { logfile, k } => stats::run(logfile, k)?,
-        args::Commands::Test { logfiles } => test::run(logfiles)?,
+        args::Commands::Stats { logfile, k, loops } => stats::run(logfile, k, loops)?,
+        #[cfg(not(feature = "timing"))]
+        args::Commands::Test { logfiles, validate } => test::run(logfiles, validate)?,
+        #[cfg(feature = "timing")]
+        args::Commands::Test {
+            logfiles,
+            validate,
+            timing,
+        } => test::run(logfiles, validate, timing)?,
+        #[cfg(feature = "serde")]
+        args::Commands::Jsonl { logfile } => jsonl::run(logfile)?,
     }
 
     Ok(())
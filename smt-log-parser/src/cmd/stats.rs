@@ -1,8 +1,13 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use smt_log_parser::{analysis::InstGraph, items::Match, LogParser, Z3Parser};
+use smt_log_parser::{
+    analysis::InstGraph,
+    display_with::{DisplayConfiguration, LayoutDirection, NameNormalizer, SymbolReplacement},
+    items::Match,
+    LogParser, Z3Parser,
+};
 
-pub fn run(logfile: PathBuf, top_k: Option<usize>) -> Result<(), String> {
+pub fn run(logfile: PathBuf, top_k: Option<usize>, loops: bool) -> Result<(), String> {
     let path = std::path::Path::new(&logfile);
     let filename = path
         .file_name()
@@ -15,8 +20,8 @@ pub fn run(logfile: PathBuf, top_k: Option<usize>) -> Result<(), String> {
 
     let (_metadata, parser) = Z3Parser::from_file(path).unwrap();
 
-    let parser = parser.process_all().map_err(|e| e.to_string())?;
-    let inst_graph = InstGraph::new(&parser).map_err(|e| format!("{e:?}"))?;
+    let mut parser = parser.process_all().map_err(|e| e.to_string())?;
+    let mut inst_graph = InstGraph::new(&parser).map_err(|e| format!("{e:?}"))?;
 
     let (no_mbqi, no_theory_solving, no_axioms, no_quantifiers) = {
         let mut no_mbqi = 0;
@@ -96,6 +101,12 @@ pub fn run(logfile: PathBuf, top_k: Option<usize>) -> Result<(), String> {
     println!("no-axioms-instantiations: {}", no_axioms);
     println!("no-quantifiers-instantiations: {}", no_quantifiers);
     println!("nodes-count: {}", inst_graph.raw.graph.node_count());
+    println!("health: {}", parser.health_summary());
+
+    println!("equality-kinds=");
+    for (kind, count) in parser.equality_kind_counts().iter() {
+        println!("{} = {}", kind, count);
+    }
 
     println!("top-instantiations=");
     let iter = instantiations_occurrances.iter();
@@ -112,5 +123,81 @@ pub fn run(logfile: PathBuf, top_k: Option<usize>) -> Result<(), String> {
         }
     }
 
+    println!("quantifier-productivity=");
+    let productivity = parser.quant_productivity();
+    for (quant, ratio) in productivity.iter().take(top_k.unwrap_or(usize::MAX)) {
+        let name = parser[*quant]
+            .kind
+            .user_name()
+            .map(|name| &parser[name])
+            .unwrap_or("-");
+        println!("{} = {:.2}", name, ratio);
+    }
+
+    println!("mbqi-only-quantifiers=");
+    for quant in parser.mbqi_only_quantifiers() {
+        let name = parser[quant]
+            .qid
+            .or(parser[quant].kind.user_name())
+            .map(|name| &parser[name])
+            .unwrap_or("-");
+        println!("{}", name);
+    }
+
+    println!("theory-axioms=");
+    for (axiom_id, insts) in parser.theory_axioms() {
+        println!("{} = {}", parser.term_id_name(axiom_id), insts.len());
+    }
+
+    if let Some(k) = top_k {
+        let costs = inst_graph.instantiation_costs();
+        println!("most-expensive-instantiations=");
+        for idx in inst_graph.top_instantiations(k) {
+            let inst = &parser[idx];
+            let quant = parser[inst.match_]
+                .kind
+                .quant_idx()
+                .and_then(|q| parser[q].qid.or(parser[q].kind.user_name()))
+                .map(|name| &parser[name])
+                .unwrap_or("-");
+            println!(
+                "{:#x} {} L{} = {}",
+                *inst.fingerprint, quant, inst.line_no, costs[idx]
+            );
+        }
+    }
+
+    if loops {
+        let matching_loops = inst_graph.find_matching_loops(&mut parser);
+        let config = DisplayConfiguration {
+            display_term_ids: false,
+            display_quantifier_name: false,
+            prefer_qid: true,
+            derived_quant_labels: false,
+            quant_name_normalizer: NameNormalizer {
+                lowercase: false,
+                strip_id: false,
+            },
+            de_bruijn_vars: false,
+            display_line_numbers: false,
+            layout_direction: LayoutDirection::TopToBottom,
+            inline_equalities: false,
+            replace_symbols: SymbolReplacement::Code,
+            html: false,
+            highlight_terms: Vec::new(),
+            enode_char_limit: None,
+            ast_depth_limit: None,
+            max_eq_steps: None,
+        };
+        println!("matching-loops={}", matching_loops.len());
+        for (i, ml) in matching_loops.iter().enumerate() {
+            println!("\n### Matching loop {}\n", i + 1);
+            let mut md = String::new();
+            ml.to_markdown(&mut md, &parser, config.clone())
+                .map_err(|e| e.to_string())?;
+            print!("{md}");
+        }
+    }
+
     Ok(())
 }
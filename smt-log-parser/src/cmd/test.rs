@@ -5,7 +5,11 @@ use smt_log_parser::parsers::LogParser;
 use std::{path::PathBuf, time::Duration};
 use wasm_timer::Instant;
 
-pub fn run(logfiles: Vec<PathBuf>) -> Result<(), String> {
+pub fn run(
+    logfiles: Vec<PathBuf>,
+    validate: bool,
+    #[cfg(feature = "timing")] timing: bool,
+) -> Result<(), String> {
     for path in logfiles {
         let path = std::path::Path::new(&path);
         let filename = path
@@ -35,6 +39,31 @@ pub fn run(logfiles: Vec<PathBuf>) -> Result<(), String> {
             },
             elapsed_time.as_secs_f32()
         );
+        #[cfg(feature = "timing")]
+        if timing {
+            let timing = result.timing();
+            println!(
+                "Parser timing: tokenizing {:?}, terms {:?}, equalities {:?}, instantiations {:?}, other {:?} (total {:?})",
+                timing.tokenizing,
+                timing.terms,
+                timing.equalities,
+                timing.instantiations,
+                timing.other,
+                timing.total(),
+            );
+        }
+        if validate {
+            let warnings = result.validate();
+            println!("Validation found {} warnings", warnings.len());
+            for warning in warnings {
+                println!("  {warning:?}");
+            }
+            let duplicates = result.duplicate_instantiations();
+            println!("Found {} duplicated instantiations", duplicates.len());
+            for (fingerprint, insts) in duplicates {
+                println!("  {:#x}: {insts:?}", *fingerprint);
+            }
+        }
         #[cfg(feature = "analysis")]
         {
             let inst_graph = InstGraph::new(&result).unwrap();
@@ -102,19 +102,99 @@ impl SymbolReplacement {
     }
 }
 
+/// The direction in which the instantiation graph flows from parents to
+/// children when laid out by Graphviz.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutDirection {
+    TopToBottom,
+    LeftToRight,
+}
+
+impl LayoutDirection {
+    /// The value of the Graphviz `rankdir` attribute.
+    pub fn rankdir(self) -> &'static str {
+        match self {
+            LayoutDirection::TopToBottom => "TB",
+            LayoutDirection::LeftToRight => "LR",
+        }
+    }
+}
+
+/// How quantifier names are normalized before being compared when searching
+/// for or grouping quantifiers, see [`Z3Parser::canonical_quant_name`]. The
+/// names stored in the parser and displayed are never changed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NameNormalizer {
+    /// Compare names ignoring case.
+    pub lowercase: bool,
+    /// Drop a trailing `!id` (e.g. `foo!12` becomes `foo`), so that the
+    /// quantifiers generated from the same source share a name.
+    pub strip_id: bool,
+}
+
+impl NameNormalizer {
+    pub fn normalize(self, name: &str) -> String {
+        let name = match name.rsplit_once('!') {
+            Some((prefix, id))
+                if self.strip_id && !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                prefix
+            }
+            _ => name,
+        };
+        if self.lowercase {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DisplayConfiguration {
     pub display_term_ids: bool,
     pub display_quantifier_name: bool,
+    /// Name quantifiers by their `qid` from the SMT file where available.
+    /// Quantifiers without one keep the `name!id` assigned by Z3.
+    pub prefer_qid: bool,
+    /// Label quantifiers which were not given a name in the SMT file by the
+    /// head symbols of their trigger, see
+    /// [`Z3Parser::derived_quant_label`].
+    pub derived_quant_labels: bool,
+    /// Applied to quantifier names when searching for quantifiers by name.
+    pub quant_name_normalizer: NameNormalizer,
+    /// Resolve the index of a bound variable against the innermost enclosing
+    /// quantifier first, as in De Bruijn notation. Otherwise the outermost
+    /// quantifier is tried first, which names the variables of nested
+    /// quantifiers after the wrong binder.
+    pub de_bruijn_vars: bool,
+    /// Add the line of the log on which an instantiation or enode was
+    /// created to its label in the graph.
+    pub display_line_numbers: bool,
+    pub layout_direction: LayoutDirection,
+    /// Draw the graph without equality nodes, showing the equality used by
+    /// each edge on the edge instead, see
+    /// `InstGraph::to_visible_inline_equalities` in the `analysis` feature.
+    pub inline_equalities: bool,
     pub replace_symbols: SymbolReplacement,
     /// Use tags for formatting
     pub html: bool,
+    /// Terms wrapped in a `<mark>` wherever they are printed, only used if
+    /// `html` is set. See [`Z3Parser::instantiated_triggers`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub highlight_terms: Vec<TermIdx>,
 
     // If `enode_char_limit` is Some, then any term longer than
     // the limit will be truncated.
     pub enode_char_limit: Option<NonMaxU32>,
     pub ast_depth_limit: Option<NonMaxU32>,
+    /// If `Some`, transitive equalities are displayed step by step, with any
+    /// steps beyond the limit replaced by `… (N more steps)`. Otherwise only
+    /// the two ends and the number of steps between them are shown.
+    pub max_eq_steps: Option<usize>,
 }
 
 mod private {
@@ -197,17 +277,24 @@ mod private {
         pub(super) fn children(&self) -> &'a [TermIdx] {
             self.children
         }
-        pub(super) fn find_quant(&self, idx: &mut usize) -> Option<&Quantifier> {
-            self.quant
-                .iter()
-                .find(|q| {
-                    let found = q.num_vars > *idx;
-                    if !found {
-                        *idx -= q.num_vars;
-                    }
-                    found
-                })
-                .copied()
+        pub(super) fn find_quant(
+            &self,
+            idx: &mut usize,
+            innermost_first: bool,
+        ) -> Option<&Quantifier> {
+            let mut binds = |q: &&&Quantifier| {
+                let found = q.num_vars > *idx;
+                if !found {
+                    *idx -= q.num_vars;
+                }
+                found
+            };
+            let quant = if innermost_first {
+                self.quant.iter().rev().find(&mut binds)
+            } else {
+                self.quant.iter().find(&mut binds)
+            };
+            quant.copied()
         }
         pub(super) fn incr_ast_depth_with_limit<T>(
             &mut self,
@@ -313,13 +400,27 @@ impl DisplayWithCtxt<DisplayCtxt<'_>, ()> for EqTransIdx {
         data: &mut (),
     ) -> fmt::Result {
         let path = ctxt.parser.egraph.equalities.path(self);
-        path.first().unwrap().fmt_with(f, ctxt, data)?;
-        if ctxt.config.html {
-            write!(f, " =<sup>{}</sup> ", path.len() - 1)?;
-        } else {
-            write!(f, " =[{}] ", path.len() - 1)?;
+        let steps = path.len() - 1;
+        let Some(max_steps) = ctxt.config.max_eq_steps else {
+            path.first().unwrap().fmt_with(f, ctxt, data)?;
+            if ctxt.config.html {
+                write!(f, " =<sup>{steps}</sup> ")?;
+            } else {
+                write!(f, " =[{steps}] ")?;
+            }
+            return path.last().unwrap().fmt_with(f, ctxt, data);
+        };
+        let shown = max_steps.min(steps);
+        path[0].fmt_with(f, ctxt, data)?;
+        for enode in &path[1..=shown] {
+            write!(f, " = ")?;
+            enode.fmt_with(f, ctxt, data)?;
         }
-        path.last().unwrap().fmt_with(f, ctxt, data)
+        if shown < steps {
+            write!(f, " … ({} more steps) = ", steps - shown)?;
+            path.last().unwrap().fmt_with(f, ctxt, data)?;
+        }
+        Ok(())
     }
 }
 
@@ -390,6 +491,10 @@ impl<'a: 'b, 'b> DisplayWithCtxt<DisplayCtxt<'b>, DisplayData<'b>> for &'a Term
         ctxt: &DisplayCtxt<'b>,
         data: &mut DisplayData<'b>,
     ) -> fmt::Result {
+        let highlight = ctxt.config.html && ctxt.config.highlight_terms.contains(&data.term);
+        if highlight {
+            write!(f, "<mark>")?;
+        }
         data.with_children(&self.child_ids, |data| {
             if ctxt.config.display_term_ids {
                 match self.id {
@@ -413,7 +518,11 @@ impl<'a: 'b, 'b> DisplayWithCtxt<DisplayCtxt<'b>, DisplayData<'b>> for &'a Term
                 write!(f, "{}", self.kind.with_data(ctxt, data))?;
             }
             Ok(())
-        })
+        })?;
+        if highlight {
+            write!(f, "</mark>")?;
+        }
+        Ok(())
     }
 }
 
@@ -455,7 +564,9 @@ impl<'a, 'b> DisplayWithCtxt<DisplayCtxt<'b>, DisplayData<'b>> for &'a TermKind
     ) -> fmt::Result {
         match self {
             &TermKind::Var(mut idx) => {
-                let vars = data.find_quant(&mut idx).and_then(|q| q.vars.as_ref());
+                let vars = data
+                    .find_quant(&mut idx, ctxt.config.de_bruijn_vars)
+                    .and_then(|q| q.vars.as_ref());
                 let name = VarNames::get_name(&ctxt.parser.strings, vars, idx, &ctxt.config);
                 write!(f, "{name}")
             }
@@ -659,7 +770,8 @@ impl<'a> DisplayWithCtxt<DisplayCtxt<'a>, DisplayData<'a>> for &'a Quantifier {
                     write!(f, "FORALL ")?;
                 }
                 if ctxt.config.display_quantifier_name {
-                    write!(f, "\"{}\" ", self.kind.with(ctxt))?;
+                    let kind = self.preferred_kind(ctxt.config.prefer_qid);
+                    write!(f, "\"{}\" ", kind.with(ctxt))?;
                 }
                 for idx in 0..self.num_vars {
                     let name = VarNames::get_name(
@@ -43,6 +43,8 @@ pub enum Error {
 
     // Id parsing
     InvalidIdNumber(ParseIntError),
+    /// The id number is too large to be represented
+    IdNumberOverflow(String),
     InvalidIdHash(String),
     UnknownId(TermId),
 
@@ -65,6 +67,10 @@ pub enum Error {
     // Instance
     UnmatchedEndOfInstance,
 
+    // Conflict
+    /// A `(not` which is not followed by a single id and a `)`.
+    InvalidConflictLit(String),
+
     TupleMissingParens,
     UnequalTupleForms(u8, u8),
 
@@ -85,6 +91,17 @@ pub enum Error {
     // File IO
     FileRead(std::io::Error),
 
+    // Fetching
+    /// The request failed without a response, e.g. due to a network error or
+    /// the server not allowing cross-origin requests.
+    FetchFailed(String),
+    /// The server responded with a non-success status code.
+    FetchStatus(u16, String),
+
+    // Graph analysis
+    /// A percentile band which does not satisfy `0.0 <= lo <= hi <= 1.0`.
+    InvalidPercentileBand(f32, f32),
+
     Allocation(TryReserveError),
 }
 
@@ -9,7 +9,8 @@ use std::ops::Index;
 
 #[macro_export]
 macro_rules! idx {
-    ($struct:ident, $prefix:tt) => {
+    ($(#[$attr:meta])* $struct:ident, $prefix:tt) => {
+        $(#[$attr])*
         #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
         #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
@@ -38,7 +39,12 @@ macro_rules! idx {
 }
 idx!(TermIdx, "t{}");
 idx!(QuantIdx, "q{}");
-idx!(InstIdx, "i{}");
+idx!(
+    /// Instantiations are numbered in the order Z3 fired them (i.e. the order
+    /// of their `[instance]` lines), so sorting by `InstIdx` replays them.
+    InstIdx,
+    "i{}"
+);
 idx!(StackIdx, "s{}");
 idx!(ENodeIdx, "e{}");
 idx!(MatchIdx, "m{}");
@@ -129,6 +135,21 @@ pub struct Quantifier {
     pub num_vars: usize,
     pub term: Option<TermIdx>,
     pub vars: Option<VarNames>,
+    /// The name given to the quantifier in the SMT file (`:qid`), or `None`
+    /// if the name in the log was generated by Z3 (`name!id`).
+    pub qid: Option<IString>,
+}
+
+impl Quantifier {
+    /// The kind used to display this quantifier: if `prefer_qid` is set and
+    /// the quantifier has a `qid` then it is shown as a named quantifier with
+    /// that name, otherwise this is the same as `kind`.
+    pub fn preferred_kind(&self, prefer_qid: bool) -> QuantKind {
+        match self.qid {
+            Some(qid) if prefer_qid => QuantKind::NamedQuant(qid),
+            _ => self.kind.clone(),
+        }
+    }
 }
 
 /// Represents an ID string of the form `name!id`.
@@ -168,6 +189,14 @@ impl QuantKind {
     pub fn is_discovered(&self) -> bool {
         matches!(self, Self::Other(_))
     }
+    /// The `:qid` of the quantifier, if it was given one in the SMT file.
+    /// Names of the form `name!id` are generated by Z3 and so not a `qid`.
+    pub fn qid(&self) -> Option<IString> {
+        match self {
+            Self::NamedQuant(name) => Some(*name),
+            _ => None,
+        }
+    }
     pub fn user_name(&self) -> Option<IString> {
         match self {
             Self::NamedQuant(name) | Self::Other(name) => Some(*name),
@@ -217,6 +246,19 @@ pub struct Instantiation {
     pub proof_id: Option<Either<TermIdx, TermId>>,
     pub z3_generation: Option<u32>,
     pub yields_terms: Box<[ENodeIdx]>,
+    /// The line of the log on which the `[instance]` appeared.
+    pub line_no: usize,
+    /// The decision level at which the instantiation happened, counted as
+    /// the number of `[push]` scopes open at the time. This includes user
+    /// scopes as well as the scopes Z3 pushes for its own case splits, so it
+    /// is an upper bound on the solver's decision level. Level 0 is before
+    /// any case split or user scope.
+    pub decision_level: u32,
+    /// The instantiation whose `[instance]` was still open (i.e. had not
+    /// reached its `[end-of-instance]`) when this one started.
+    pub parent_instantiation: Option<InstIdx>,
+    /// The number of enclosing open instantiations, `0` if there are none.
+    pub nesting_depth: u32,
 }
 
 impl Instantiation {
@@ -225,6 +267,38 @@ impl Instantiation {
     }
 }
 
+/// Identifies an instantiation across traces, where interned strings and
+/// indices differ: the resolved name of the quantifier (or theory for theory
+/// solving), the fingerprint and the shape of the bound terms. See
+/// [`Z3Parser::inst_key`].
+///
+/// [`Z3Parser::inst_key`]: crate::Z3Parser::inst_key
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InstKey {
+    pub quant: String,
+    pub fingerprint: Fingerprint,
+    /// The [`structural_hash`](crate::Z3Parser::structural_hash) of each
+    /// bound term. A summary parser keeps no term structure, so these are
+    /// all `None` there and instantiations of the same quantifier (or
+    /// theory) with the same fingerprint share a key, which in particular
+    /// merges all theory solving instantiations of a theory. Only compare
+    /// keys from parsers which are not summaries.
+    pub bound_terms: Vec<Option<u64>>,
+}
+
+/// A literal of the clause of a `[conflict]` line.
+#[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct ConflictLit {
+    pub term: TermIdx,
+    /// The literal is `(not term)`.
+    pub negated: bool,
+    /// The enode of `term` at the time of the conflict, if there was one.
+    pub enode: Option<ENodeIdx>,
+}
+
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
@@ -330,8 +404,18 @@ impl MatchKind {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
 pub enum BlameKind {
-    Term { term: ENodeIdx },
-    Equality { eq: EqTransIdx },
+    Term {
+        term: ENodeIdx,
+    },
+    Equality {
+        eq: EqTransIdx,
+    },
+    /// An equality between the two enodes which was not tracked since the
+    /// parser skips equalities, see `Z3Parser::new_without_equalities`.
+    UnknownEquality {
+        from: ENodeIdx,
+        to: ENodeIdx,
+    },
 }
 impl BlameKind {
     fn unwrap_enode(&self) -> &ENodeIdx {
@@ -361,10 +445,21 @@ impl<'a> Blame<'a> {
     }
 
     pub fn equalities_len(self) -> usize {
-        self.slice.len() - 1
+        self.equalities().count()
     }
     pub fn equalities(self) -> impl Iterator<Item = EqTransIdx> + 'a {
-        self.slice.iter().skip(1).map(|x| *x.unwrap_eq())
+        self.slice.iter().skip(1).filter_map(|x| match x {
+            BlameKind::Equality { eq } => Some(*eq),
+            _ => None,
+        })
+    }
+    /// The equalities used which were not tracked, these are only present
+    /// if the parser skips equalities (and then there are no `equalities`).
+    pub fn unknown_equalities(self) -> impl Iterator<Item = (ENodeIdx, ENodeIdx)> + 'a {
+        self.slice.iter().skip(1).filter_map(|x| match x {
+            BlameKind::UnknownEquality { from, to } => Some((*from, *to)),
+            _ => None,
+        })
     }
 }
 impl Index<usize> for Blame<'_> {
@@ -421,7 +516,12 @@ impl TermId {
         let id = &value[hash_idx + 1..];
         let id = match id {
             "" => None,
-            id => Some(NonMaxU32::new(id.parse::<u32>().map_err(Error::InvalidIdNumber)?).unwrap()),
+            id => {
+                let id = id.parse::<u32>().map_err(Error::InvalidIdNumber)?;
+                let id =
+                    NonMaxU32::new(id).ok_or_else(|| Error::IdNumberOverflow(value.to_string()))?;
+                Some(id)
+            }
         };
         Ok(Self { namespace, id })
     }
@@ -491,7 +591,7 @@ impl TermIdToIdxMap {
 /// All other variants represent an equality between two terms and where it came from.
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EqualityExpl {
     Root {
         id: ENodeIdx,
@@ -578,6 +678,34 @@ impl EqualityExpl {
     }
 }
 
+/// The number of given equalities of each [`EqualityExpl`] kind, see
+/// [`Z3Parser::equality_kind_counts`](crate::Z3Parser::equality_kind_counts).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EqKindCounts {
+    pub root: usize,
+    pub literal: usize,
+    pub congruence: usize,
+    pub theory: usize,
+    pub axiom: usize,
+    pub unknown: usize,
+}
+
+impl EqKindCounts {
+    /// Each count paired with the [`EqualityExpl::short_str`] of its kind.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, usize)> {
+        [
+            ("root", self.root),
+            ("literal", self.literal),
+            ("congruence", self.congruence),
+            ("theory", self.theory),
+            ("axiom", self.axiom),
+            ("unknown", self.unknown),
+        ]
+        .into_iter()
+    }
+}
+
 // Whenever a pair of enodes are said to be equal this uses transitive reasoning
 // with one or more `EqualityExpl` to explain why.
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
@@ -27,6 +27,8 @@ macro_rules! copy_impl {
 copy_impl!(NonMaxU32);
 copy_impl!(NonMaxUsize);
 copy_impl!(IString);
+#[cfg(feature = "timing")]
+copy_impl!(crate::parsers::z3::timing::ParseTiming);
 
 // TiVec
 
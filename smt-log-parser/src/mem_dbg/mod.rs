@@ -2,7 +2,10 @@
 mod r#impl;
 
 use core::fmt;
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
 
 macro_rules! derive_wrapper {
     ($head:ident $(:: $tail:ident)+ $(<$($rest1:tt)*)? $(: $($rest2:tt)*)?) => {
@@ -18,9 +21,11 @@ macro_rules! derive_wrapper {
         );
     };
     (
+        $(#[doc = $doc:literal])*
         $(#[derive($($d:ident),*)])?
         struct $struct:ident$(<$($t:ident$(= $default:ty)?),*>)?($p:vis $inner:ty);
     ) => {
+        $(#[doc = $doc])*
         $(#[derive($($d),*)])?
         pub struct $struct$(<$($t$(= $default)?),*>)?($p $inner);
         impl$(<$($t),*>)? Deref for $struct$(<$($t),*>)? {
@@ -115,6 +120,11 @@ impl<K, V> Default for TiVec<K, V> {
         Self(typed_index_collections::TiVec::default())
     }
 }
+impl<K, V> FromIterator<V> for TiVec<K, V> {
+    fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+        Self(typed_index_collections::TiVec::from_iter(iter))
+    }
+}
 
 // FxHashMap
 
@@ -127,13 +137,54 @@ impl<K, V> Default for FxHashMap<K, V> {
 
 // StringTable
 
-derive_wrapper!(
-    // #[derive(Default)]
-    struct StringTable(lasso::Rodeo<lasso::Spur, fxhash::FxBuildHasher>);
-);
+/// An append-only interner for all strings found in a log. Strings can
+/// only be resolved with the table (or a later version of the table) that
+/// interned them. See [`Z3Parser::with_strings`](crate::Z3Parser::with_strings)
+/// for how to reuse one table across multiple parsers.
+///
+/// Clones are cheap and share their strings through an [`Rc`]: a table only
+/// makes its own copy of the strings when it interns a new one while
+/// shared. Because of the `Rc` a table, and so a parser, cannot be sent to
+/// another thread; parse traces on separate threads with separate tables.
+#[derive(Clone)]
+pub struct StringTable(Rc<lasso::Rodeo<lasso::Spur, fxhash::FxBuildHasher>>);
 impl StringTable {
     pub fn with_hasher(hash_builder: fxhash::FxBuildHasher) -> Self {
-        Self(lasso::Rodeo::with_hasher(hash_builder))
+        Self(Rc::new(lasso::Rodeo::with_hasher(hash_builder)))
+    }
+}
+impl Deref for StringTable {
+    type Target = lasso::Rodeo<lasso::Spur, fxhash::FxBuildHasher>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl DerefMut for StringTable {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        Rc::make_mut(&mut self.0)
+    }
+}
+impl fmt::Debug for StringTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for StringTable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (*self.0).serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StringTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::Deserialize::deserialize(deserializer).map(|table| Self(Rc::new(table)))
     }
 }
 
@@ -147,7 +198,7 @@ derive_wrapper!(
 // BoxSlice
 
 derive_wrapper!(
-    #[derive(PartialEq)]
+    #[derive(PartialEq, Eq, Hash)]
     struct BoxSlice<T>(pub Box<[T]>);
 );
 impl<T> BoxSlice<T> {
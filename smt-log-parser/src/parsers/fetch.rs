@@ -0,0 +1,55 @@
+use crate::{Error, Result, Z3Parser};
+
+#[cfg(target_arch = "wasm32")]
+use super::{AsyncBufferRead, AsyncParser, IntoAsyncParser};
+#[cfg(not(target_arch = "wasm32"))]
+use super::{IntoStreamParser, StreamParser};
+
+/// Requests the trace at `url` and returns a parser streaming the response
+/// body, along with the size of the body if the server reported it (for
+/// progress reporting). Uses a blocking request, so this must not be called
+/// from within an async runtime.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fetch_and_parse(url: &str) -> Result<(Option<u64>, StreamParser<'static, Z3Parser>)> {
+    let response =
+        reqwest::blocking::get(url).map_err(|err| Error::FetchFailed(err.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let reason = status.canonical_reason().unwrap_or_default();
+        return Err(Error::FetchStatus(status.as_u16(), reason.to_string()));
+    }
+    let size = response.content_length();
+    Ok((size, std::io::BufReader::new(response).into_parser()))
+}
+
+/// Requests the trace at `url` with the browser's `fetch` and returns a parser
+/// streaming the response body, along with the size of the body if the server
+/// reported it (for progress reporting). The server must allow cross-origin
+/// requests, otherwise this fails with [`Error::FetchFailed`].
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_and_parse(url: &str) -> Result<(Option<u64>, AsyncParser<'static, Z3Parser>)> {
+    use wasm_bindgen::JsCast;
+
+    let response = gloo_net::http::Request::get(url)
+        .send()
+        .await
+        .map_err(|err| Error::FetchFailed(err.to_string()))?;
+    if !response.ok() {
+        return Err(Error::FetchStatus(
+            response.status(),
+            response.status_text(),
+        ));
+    }
+    let size = response
+        .headers()
+        .get("content-length")
+        .and_then(|len| len.parse().ok());
+    let body = response
+        .body()
+        .ok_or_else(|| Error::FetchFailed("response has no body".to_string()))?;
+    let stream = wasm_streams::ReadableStream::from_raw(body.unchecked_into());
+    let reader = stream
+        .try_into_async_read()
+        .map_err(|(err, _)| Error::FetchFailed(String::from(err.to_string())))?;
+    Ok((size, reader.buffer().into_async_parser()))
+}
@@ -1,6 +1,8 @@
 use crate::FResult;
 use crate::FatalError;
 
+#[cfg(feature = "fetch")]
+pub use self::fetch::*;
 pub use self::wrapper_async_parser::*;
 pub use self::wrapper_stream_parser::*;
 use futures::{AsyncBufRead, AsyncBufReadExt, AsyncRead};
@@ -8,9 +10,15 @@ use std::fmt::Debug;
 use std::fs::{File, Metadata};
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use std::time::Duration;
 use wasm_timer::Instant;
 
+#[cfg(feature = "fetch")]
+mod fetch;
 pub mod z3;
 
 /// Trait for a generic SMT solver trace parser. Intended to support different
@@ -20,6 +28,13 @@ pub trait LogParser: Default {
     fn is_line_start(&mut self, _first_byte: u8) -> bool {
         true
     }
+    /// Called with the entry read so far whenever another line could be
+    /// appended to it. Returning `false` appends the next line regardless of
+    /// [`is_line_start`](Self::is_line_start), e.g. while inside of a quoted
+    /// symbol which contains a newline.
+    fn is_entry_complete(&mut self, _entry: &str) -> bool {
+        true
+    }
 
     /// Process a single line of the log file. Return `true` if parsing should
     /// continue, or `false` if parsing should stop.
@@ -195,6 +210,16 @@ mod wrapper {
                 parser: Parser::default(),
             }
         }
+        /// Creates a new parser wrapper around an already constructed
+        /// `parser`, e.g. one which was set up to share its string table with
+        /// a previously parsed trace.
+        pub fn with_parser(reader: impl ReadBound, parser: Parser) -> Self {
+            Self {
+                reader: Some(Box::new(reader)),
+                reader_state: ReaderState::default(),
+                parser,
+            }
+        }
 
         /// Get the current parser state.
         pub fn parser(&self) -> &Parser {
@@ -228,7 +253,9 @@ mod wrapper {
                 reader_state.lines_read += 1;
                 let peek = add_await([reader.fill_buf()])?;
                 // Stop reading if this is the end or we don't have a multiline.
-                if peek.is_empty() || parser.is_line_start(peek[0]) {
+                if peek.is_empty()
+                    || (parser.is_entry_complete(buf) && parser.is_line_start(peek[0]))
+                {
                     break;
                 }
             }
@@ -407,6 +434,22 @@ mod wrapper {
             let result = add_await([self.process_check_every(timeout, |_, _| Some(()))]);
             (result, self.parser)
         }
+        /// Try to parse everything, but stop as soon as `token` is set (e.g.
+        /// from another thread). The result tuple contains
+        /// `ParseState::Paused(read_info)` if parsing was cancelled, and the
+        /// parser state at the end. A cancelled parser holds everything up to
+        /// the line where it stopped and can still be analysed.
+        ///
+        /// Parsing cannot be resumed once cancelled. If you need support for
+        /// resuming, use [`process_until`] instead.
+        pub async fn process_all_cancellable(
+            mut self,
+            token: Arc<AtomicBool>,
+        ) -> (ParseState<()>, Parser) {
+            let result =
+                add_await([self.process_until(|_, _| token.load(Ordering::Relaxed).then_some(()))]);
+            (result, self.parser)
+        }
         /// Try to parse everything, but stop after parsing `limit` bytes. The
         /// result tuple contains `ParseState::Paused(read_info)` if the limit
         /// was reached, and the parser state at the end (i.e. the state is
@@ -0,0 +1,65 @@
+use fxhash::FxHashSet;
+
+use crate::{
+    items::{ENodeIdx, EqualityExpl, QuantIdx},
+    FxHashMap,
+};
+
+use super::z3parser::Z3Parser;
+
+impl Z3Parser {
+    /// For each quantifier with at least one match, the number of its
+    /// matches (`[new-match]` lines, including those never instantiated) and
+    /// of its instantiations. Sorted by the number of matches which were not
+    /// instantiated, largest first: a quantifier whose triggers match often
+    /// but rarely lead to an instantiation makes Z3 do a lot of unproductive
+    /// e-matching work.
+    pub fn ematching_pressure(&self) -> Vec<(QuantIdx, usize, usize)> {
+        let mut counts = FxHashMap::<QuantIdx, (usize, usize)>::default();
+        for match_ in self.insts.matches.iter() {
+            if let Some(quant) = match_.kind.quant_idx() {
+                counts.entry(quant).or_default().0 += 1;
+            }
+        }
+        for (_, inst) in self.instantiations() {
+            if let Some(quant) = self[inst.match_].kind.quant_idx() {
+                counts.entry(quant).or_default().1 += 1;
+            }
+        }
+        let mut pressure: Vec<_> = counts
+            .iter()
+            .map(|(&quant, &(matches, insts))| (quant, matches, insts))
+            .collect();
+        pressure.sort_unstable_by_key(|&(quant, matches, insts)| {
+            (std::cmp::Reverse(matches.saturating_sub(insts)), quant)
+        });
+        pressure
+    }
+
+    /// Pairs of enodes which were explained equal by at least `threshold`
+    /// distinct given equalities (in either direction), with that number,
+    /// most explained first. Z3 proving the same two enodes equal over and
+    /// over with alternating justifications can indicate an equality
+    /// saturation loop, the equality analogue of a matching loop. The smaller
+    /// enode of each pair comes first.
+    ///
+    /// Two enodes are commonly explained equal once, or twice when the
+    /// equality is re-derived differently after a pop, so a `threshold` of
+    /// `3` is a sensible starting point. Lower values mostly report noise.
+    pub fn ping_pong_equalities(&self, threshold: usize) -> Vec<(ENodeIdx, ENodeIdx, usize)> {
+        let graph = self.equality_graph();
+        let mut pairs = FxHashMap::<(ENodeIdx, ENodeIdx), FxHashSet<&EqualityExpl>>::default();
+        for edge in graph.raw_edges() {
+            let (from, to) = (graph[edge.source()], graph[edge.target()]);
+            let expls = pairs.entry((from.min(to), from.max(to))).or_default();
+            expls.insert(&edge.weight);
+        }
+        let mut ping_pong: Vec<_> = pairs
+            .iter()
+            .filter(|(_, expls)| expls.len() >= threshold)
+            .map(|(&(a, b), expls)| (a, b, expls.len()))
+            .collect();
+        ping_pong.sort_by(|x, y| y.2.cmp(&x.2).then_with(|| (x.0, x.1).cmp(&(y.0, y.1))));
+        ping_pong
+    }
+}
@@ -0,0 +1,201 @@
+use std::cmp::Ordering;
+
+use crate::{
+    items::{ENodeIdx, EqGivenIdx, EqTransIdx, EqualityExpl, InstIdx, TransitiveExplSegmentKind},
+    NonMaxU32, TiVec,
+};
+
+use super::z3parser::Z3Parser;
+
+/// The order used for every ranking by cost: most expensive first, with ties
+/// broken by the lower index. Pass it to `sort_by` with the cost of each
+/// index, e.g. `nodes.sort_by(cost_order(|n: RawNodeIndex| raw[n].cost))`,
+/// so that all rankings agree.
+pub fn cost_order<I: Ord + Copy>(cost: impl Fn(I) -> f64) -> impl Fn(&I, &I) -> Ordering {
+    move |&a, &b| cost(b).total_cmp(&cost(a)).then_with(|| a.cmp(&b))
+}
+
+/// The default cost of every enode, given equality (one per use of a
+/// congruence), transitive equality and instantiation of a trace. Each
+/// instantiation starts with a cost of 1 and hands its cost on to the enodes
+/// and equalities it was blamed on, which hand it on to whatever created
+/// them. A cost is split evenly between everything it is handed on to,
+/// except for transitive equalities without any given equalities, which get
+/// no share. These are the costs the `InstGraph` starts with.
+#[derive(Debug, Clone)]
+pub struct DefaultCosts {
+    pub enodes: TiVec<ENodeIdx, f64>,
+    pub given: TiVec<EqGivenIdx, Box<[f64]>>,
+    pub transitive: TiVec<EqTransIdx, f64>,
+    pub insts: TiVec<InstIdx, f64>,
+}
+
+impl DefaultCosts {
+    /// The cost of the given equality `eq`, `use_` is `None` unless `eq` is
+    /// a congruence.
+    pub fn given(&self, eq: EqGivenIdx, use_: Option<NonMaxU32>) -> f64 {
+        self.given[eq][use_.map_or(0, |u| u.get() as usize)]
+    }
+}
+
+/// Anything a cost can be handed on to.
+#[derive(Debug, Clone, Copy)]
+enum Item {
+    ENode(ENodeIdx),
+    Given(EqGivenIdx, usize),
+    Transitive(EqTransIdx),
+    Inst(InstIdx),
+}
+
+/// A value for each [`Item`] of a trace.
+struct PerItem<T> {
+    enodes: TiVec<ENodeIdx, T>,
+    given: TiVec<EqGivenIdx, Box<[T]>>,
+    transitive: TiVec<EqTransIdx, T>,
+    insts: TiVec<InstIdx, T>,
+}
+
+impl<T: Clone> PerItem<T> {
+    fn new(parser: &Z3Parser, value: T) -> Self {
+        let equalities = &parser.egraph.equalities;
+        Self {
+            enodes: parser.egraph.enodes.iter().map(|_| value.clone()).collect(),
+            given: (equalities.given.iter())
+                .map(|eq| vec![value.clone(); uses(eq)].into())
+                .collect(),
+            transitive: equalities
+                .transitive
+                .iter()
+                .map(|_| value.clone())
+                .collect(),
+            insts: parser.insts.insts.iter().map(|_| value.clone()).collect(),
+        }
+    }
+    fn items(&self) -> impl Iterator<Item = Item> + '_ {
+        let enodes = self.enodes.keys().map(Item::ENode);
+        let given = self
+            .given
+            .iter_enumerated()
+            .flat_map(|(eq, uses)| (0..uses.len()).map(move |use_| Item::Given(eq, use_)));
+        let transitive = self.transitive.keys().map(Item::Transitive);
+        let insts = self.insts.keys().map(Item::Inst);
+        enodes.chain(given).chain(transitive).chain(insts)
+    }
+    fn get_mut(&mut self, item: Item) -> &mut T {
+        match item {
+            Item::ENode(enode) => &mut self.enodes[enode],
+            Item::Given(eq, use_) => &mut self.given[eq][use_],
+            Item::Transitive(eq) => &mut self.transitive[eq],
+            Item::Inst(inst) => &mut self.insts[inst],
+        }
+    }
+}
+
+/// The number of nodes of a given equality in the `InstGraph`.
+fn uses(eq: &EqualityExpl) -> usize {
+    match eq {
+        EqualityExpl::Congruence { uses, .. } => uses.len(),
+        _ => 1,
+    }
+}
+
+impl Z3Parser {
+    /// Calls `f` with everything `item` hands its cost on to, once for each
+    /// time it depends on it.
+    fn for_each_cost_parent(&self, item: Item, mut f: impl FnMut(Item)) {
+        match item {
+            Item::ENode(enode) => {
+                if let Some(inst) = self[enode].created_by {
+                    f(Item::Inst(inst))
+                }
+            }
+            Item::Given(eq, use_) => match &self.egraph.equalities.given[eq] {
+                EqualityExpl::Literal { eq, .. } => f(Item::ENode(*eq)),
+                EqualityExpl::Congruence { uses, .. } => {
+                    uses[use_].iter().for_each(|&eq| f(Item::Transitive(eq)))
+                }
+                _ => (),
+            },
+            Item::Transitive(eq) => {
+                for segment in self.egraph.equalities.transitive[eq].all(true) {
+                    f(match segment.kind {
+                        TransitiveExplSegmentKind::Given(eq, use_) => {
+                            Item::Given(eq, use_.map_or(0, |u| u.get() as usize))
+                        }
+                        TransitiveExplSegmentKind::Transitive(eq) => Item::Transitive(eq),
+                    })
+                }
+            }
+            Item::Inst(inst) => {
+                for blame in self[self[inst].match_].trigger_matches() {
+                    f(Item::ENode(blame.enode()));
+                    blame.equalities().for_each(|eq| f(Item::Transitive(eq)));
+                }
+            }
+        }
+    }
+
+    /// The share of the cost handed on to `item`.
+    fn cost_weight(&self, item: Item) -> usize {
+        match item {
+            Item::Transitive(eq) => self[eq].given_len.min(1),
+            _ => 1,
+        }
+    }
+
+    /// The [`DefaultCosts`] of the trace, where the instantiations for which
+    /// `disabled` returns true start with a cost of 0 rather than 1.
+    pub fn default_costs(&self, disabled: impl Fn(InstIdx) -> bool) -> DefaultCosts {
+        let mut costs = PerItem::new(self, 0.0);
+        for (inst, cost) in costs.insts.iter_mut_enumerated() {
+            *cost = if disabled(inst) { 0.0 } else { 1.0 };
+        }
+        // Hand on costs in reverse topological order: an item is ready once
+        // everything depending on it has handed on its cost.
+        let mut dependents = PerItem::new(self, 0_usize);
+        let items: Vec<_> = dependents.items().collect();
+        for &item in &items {
+            self.for_each_cost_parent(item, |parent| *dependents.get_mut(parent) += 1);
+        }
+        let mut ready: Vec<_> = items
+            .into_iter()
+            .filter(|&item| *dependents.get_mut(item) == 0)
+            .collect();
+        let mut parents = Vec::new();
+        while let Some(item) = ready.pop() {
+            parents.clear();
+            self.for_each_cost_parent(item, |parent| parents.push(parent));
+            let total: usize = parents.iter().map(|&p| self.cost_weight(p)).sum();
+            let cost = *costs.get_mut(item);
+            for &parent in &parents {
+                *costs.get_mut(parent) += cost * self.cost_weight(parent) as f64 / total as f64;
+                let pending = dependents.get_mut(parent);
+                *pending -= 1;
+                if *pending == 0 {
+                    ready.push(parent);
+                }
+            }
+        }
+        DefaultCosts {
+            enodes: costs.enodes,
+            given: costs.given,
+            transitive: costs.transitive,
+            insts: costs.insts,
+        }
+    }
+
+    /// The default cost of each instantiation, see [`DefaultCosts`].
+    pub fn instantiation_costs(&self) -> TiVec<InstIdx, f64> {
+        self.default_costs(|_| false).insts
+    }
+
+    /// The `k` most expensive instantiations by their
+    /// [`Z3Parser::instantiation_costs`], in the order of [`cost_order`].
+    pub fn top_instantiations(&self, k: usize) -> Vec<InstIdx> {
+        let costs = self.instantiation_costs();
+        let mut insts: Vec<_> = costs.keys().collect();
+        insts.sort_by(cost_order(|i: InstIdx| costs[i]));
+        insts.truncate(k);
+        insts
+    }
+}
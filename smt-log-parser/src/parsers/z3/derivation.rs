@@ -0,0 +1,99 @@
+use crate::items::{
+    ENodeIdx, EqGivenIdx, EqTransIdx, EqualityExpl, InstIdx, TransitiveExplSegmentKind,
+};
+
+use super::z3parser::Z3Parser;
+
+/// How an instantiation came about: the terms its trigger matched, the
+/// equalities needed for that and, recursively, the instantiations which
+/// produced those, see [`Z3Parser::derivation_of`]. Unlike the instantiation
+/// graph this is a tree, so an instantiation used in several places appears
+/// once in each.
+#[derive(Debug, Clone)]
+pub struct Derivation {
+    pub inst: InstIdx,
+    /// One entry per term matched by the trigger, in the order of the
+    /// trigger. `None` if the depth bound was reached at this instantiation.
+    pub matched: Option<Vec<MatchedTerm>>,
+}
+
+/// A term matched by the trigger of the instantiation of a [`Derivation`].
+#[derive(Debug, Clone)]
+pub struct MatchedTerm {
+    pub enode: ENodeIdx,
+    /// The instantiation which yielded the term, `None` if the term was not
+    /// produced by an instantiation, e.g. because it was asserted.
+    pub created_by: Option<Box<Derivation>>,
+    /// The given equalities which were needed to match the term against the
+    /// trigger, in the order in which they are chained.
+    pub equalities: Vec<DerivedEquality>,
+}
+
+/// A single given equality used in a [`MatchedTerm`]. Why it holds is given
+/// by the kind of its [`EqualityExpl`], see [`EqualityExpl::short_str`].
+#[derive(Debug, Clone)]
+pub struct DerivedEquality {
+    pub given: EqGivenIdx,
+    /// For [`EqualityExpl::Literal`] equalities the instantiation which
+    /// yielded the equality term, if any.
+    pub created_by: Option<Box<Derivation>>,
+}
+
+impl Z3Parser {
+    /// The derivation of instantiation `i` back to asserted terms, cut off
+    /// after `max_depth` instantiations: the number of instantiations on a
+    /// path from the root to a leaf is at most `max_depth + 1`, those at the
+    /// bound have no [`matched`](Derivation::matched) terms. The size of the
+    /// tree can grow exponentially in `max_depth`. Equalities which were not
+    /// tracked by the parser are left out.
+    pub fn derivation_of(&self, i: InstIdx, max_depth: usize) -> Derivation {
+        let Some(depth) = max_depth.checked_sub(1) else {
+            return Derivation {
+                inst: i,
+                matched: None,
+            };
+        };
+        let created_by =
+            |inst: Option<InstIdx>| inst.map(|inst| Box::new(self.derivation_of(inst, depth)));
+        let matched = self[self[i].match_]
+            .trigger_matches()
+            .map(|blame| {
+                let enode = blame.enode();
+                let mut givens = Vec::new();
+                for eq in blame.equalities() {
+                    self.given_equalities(eq, &mut givens);
+                }
+                let equalities = givens
+                    .into_iter()
+                    .map(|given| DerivedEquality {
+                        given,
+                        created_by: match &self[given] {
+                            EqualityExpl::Literal { eq, .. } => created_by(self[*eq].created_by),
+                            _ => None,
+                        },
+                    })
+                    .collect();
+                MatchedTerm {
+                    enode,
+                    created_by: created_by(self[enode].created_by),
+                    equalities,
+                }
+            })
+            .collect();
+        Derivation {
+            inst: i,
+            matched: Some(matched),
+        }
+    }
+
+    /// Flattens the transitive equality `eq` into the given equalities it is
+    /// chained from.
+    fn given_equalities(&self, eq: EqTransIdx, givens: &mut Vec<EqGivenIdx>) {
+        for segment in self[eq].all(true) {
+            match segment.kind {
+                TransitiveExplSegmentKind::Given(given, _) => givens.push(given),
+                TransitiveExplSegmentKind::Transitive(eq) => self.given_equalities(eq, givens),
+            }
+        }
+    }
+}
@@ -32,6 +32,7 @@ impl EGraph {
         created_by: Option<InstIdx>,
         term: TermIdx,
         z3_generation: Option<u32>,
+        line_no: Option<usize>,
         stack: &Stack,
     ) -> Result<ENodeIdx> {
         // TODO: why does this happen sometimes?
@@ -48,6 +49,7 @@ impl EGraph {
             created_by,
             owner: term,
             z3_generation,
+            line_no,
             equalities: Vec::new(),
             transitive: FxHashMap::default(),
             self_transitive: None,
@@ -80,6 +82,23 @@ impl EGraph {
         self.enodes[enode].owner
     }
 
+    /// Returns whether the two enodes are in the same equivalence class, i.e.
+    /// following their most recent equalities leads to the same root.
+    pub fn are_equal(&self, a: ENodeIdx, b: ENodeIdx, stack: &Stack) -> Result<bool> {
+        if a == b {
+            return Ok(true);
+        }
+        Ok(self.root(a, stack)? == self.root(b, stack)?)
+    }
+
+    /// The representative of the equivalence class of `enode`: the root
+    /// reached by following its most recent equalities. Enodes are
+    /// [equal](EGraph::are_equal) iff they have the same root.
+    pub fn root(&self, enode: ENodeIdx, stack: &Stack) -> Result<ENodeIdx> {
+        let (_, path) = self.path_to_root(enode, None, stack)?;
+        Ok(*path.last().unwrap())
+    }
+
     pub fn new_given_equality(
         &mut self,
         from: ENodeIdx,
@@ -379,6 +398,9 @@ pub struct ENode {
     pub created_by: Option<InstIdx>,
     pub owner: TermIdx,
     pub z3_generation: Option<u32>,
+    /// The line of the log on which the `[attach-enode]` appeared, `None` if
+    /// the enode was created without one.
+    pub line_no: Option<usize>,
 
     equalities: Vec<Equality>,
     /// This will never contain a `TransitiveExpl::to` pointing to itself. It
@@ -0,0 +1,165 @@
+use std::{fmt, io::BufRead};
+
+use crate::{
+    items::{InstIdx, QuantIdx},
+    parsers::{ParseState, StreamParser},
+    TiVec,
+};
+
+use super::z3parser::Z3Parser;
+
+/// The length of a chain of instantiations of the same quantifier, each
+/// triggered by a term yielded by the previous one, from which on the
+/// quantifier is reported as a likely matching loop.
+pub const MATCHING_LOOP_CHAIN: usize = 10;
+/// The share of all instantiations which must be theory solving for the
+/// trace to be reported as theory solving dominated.
+pub const THEORY_SOLVING_SHARE: f32 = 0.5;
+/// How many lines [`Z3Parser::parse_until_loop`] parses between checks for a
+/// matching loop.
+pub const LOOP_CHECK_LINES: usize = 1_000_000;
+
+/// The overall verdict of a [`HealthSummary`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HealthVerdict {
+    /// The quantifier triggers itself over a long chain of instantiations.
+    LikelyMatchingLoop(QuantIdx),
+    /// Most instantiations come from theory solving rather than quantifiers.
+    TheorySolvingDominated,
+    LooksFine,
+}
+
+/// A quick triage of a trace, see [`Z3Parser::health_summary`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthSummary {
+    pub verdict: HealthVerdict,
+    pub instantiations: usize,
+    /// The quantifier with the most instantiations and its share of all
+    /// instantiations.
+    pub most_instantiated: Option<(QuantIdx, f32)>,
+    /// The quantifier with the longest chain of instantiations where each
+    /// was triggered by a term yielded by the previous one, and the length
+    /// of that chain.
+    pub longest_self_chain: Option<(QuantIdx, usize)>,
+    /// The length of the longest chain of instantiations where each was
+    /// triggered by a term yielded by the previous one, of any quantifiers.
+    pub longest_chain: usize,
+    /// The share of all instantiations which are theory solving.
+    pub theory_solving_share: f32,
+}
+
+impl Z3Parser {
+    /// Combines a few cheap heuristics into a single verdict on the trace:
+    /// quantifiers which trigger themselves over a chain of at least
+    /// [`MATCHING_LOOP_CHAIN`] instantiations, and the share of
+    /// instantiations which are theory solving. Only terms blamed directly
+    /// are followed, equalities are ignored. Display the result for a one
+    /// line summary.
+    pub fn health_summary(&self) -> HealthSummary {
+        let mut counts: TiVec<QuantIdx, usize> = self.quantifiers.iter().map(|_| 0).collect();
+        let mut theory_solving = 0;
+        // The longest chain ending at each instantiation, of any quantifiers
+        // and of only its own quantifier.
+        let mut chain = TiVec::<InstIdx, (usize, usize)>::default();
+        for (inst, data) in self.instantiations() {
+            let quant = self[data.match_].kind.quant_idx();
+            match quant {
+                Some(q) => counts[q] += 1,
+                None => theory_solving += 1,
+            }
+            let (mut any, mut same) = (0, 0);
+            for blame in self[data.match_].trigger_matches() {
+                let Some(parent) = self[blame.enode()].created_by.filter(|&p| p < inst) else {
+                    continue;
+                };
+                any = any.max(chain[parent].0);
+                if quant.is_some() && self[self[parent].match_].kind.quant_idx() == quant {
+                    same = same.max(chain[parent].1);
+                }
+            }
+            chain.push((any + 1, same + 1));
+        }
+
+        let instantiations = chain.len();
+        let share = |count: usize| count as f32 / instantiations.max(1) as f32;
+        let most_instantiated = counts
+            .iter_enumerated()
+            .filter(|(_, &count)| count > 0)
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(q, &count)| (q, share(count)));
+        let longest_self_chain = chain
+            .iter_enumerated()
+            .filter_map(|(i, &(_, same))| {
+                let q = self[self[i].match_].kind.quant_idx()?;
+                Some((q, same))
+            })
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+        let longest_chain = chain.iter().map(|&(any, _)| any).max().unwrap_or(0);
+        let theory_solving_share = share(theory_solving);
+
+        let verdict = match longest_self_chain {
+            Some((q, len)) if len >= MATCHING_LOOP_CHAIN => HealthVerdict::LikelyMatchingLoop(q),
+            _ if theory_solving_share >= THEORY_SOLVING_SHARE => {
+                HealthVerdict::TheorySolvingDominated
+            }
+            _ => HealthVerdict::LooksFine,
+        };
+        HealthSummary {
+            verdict,
+            instantiations,
+            most_instantiated,
+            longest_self_chain,
+            longest_chain,
+            theory_solving_share,
+        }
+    }
+
+    /// Parses the log from `reader` but stops as soon as a quantifier is
+    /// likely in a matching loop, as detected by [`Z3Parser::health_summary`].
+    /// The check runs every [`LOOP_CHECK_LINES`] lines. The result tuple
+    /// contains `ParseState::Paused(quant, read_info)` with the suspect
+    /// quantifier if parsing was stopped early, and the (partial) parser.
+    ///
+    /// Parsing cannot be resumed once stopped. If you need support for
+    /// resuming, use [`StreamParser::process_until_every`] instead.
+    pub fn parse_until_loop<'r>(reader: impl BufRead + 'r) -> (ParseState<QuantIdx>, Self) {
+        let mut parser = StreamParser::<'r, Self>::from(reader);
+        let result = parser.process_until_every(
+            |p, _| match p.health_summary().verdict {
+                HealthVerdict::LikelyMatchingLoop(q) => Some(q),
+                _ => None,
+            },
+            LOOP_CHECK_LINES,
+        );
+        (result, parser.take_parser())
+    }
+}
+
+impl fmt::Display for HealthSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.verdict {
+            HealthVerdict::LikelyMatchingLoop(q) => {
+                let len = self.longest_self_chain.map_or(0, |(_, len)| len);
+                write!(
+                    f,
+                    "likely matching loop in quantifier {q:?} (chain of {len} self-triggered instantiations"
+                )?;
+                if let Some((_, share)) = self.most_instantiated.filter(|&(top, _)| top == q) {
+                    write!(f, ", {:.0}% of all instantiations", share * 100.0)?;
+                }
+                write!(f, ")")
+            }
+            HealthVerdict::TheorySolvingDominated => write!(
+                f,
+                "theory-solving dominated ({:.0}% of {} instantiations)",
+                self.theory_solving_share * 100.0,
+                self.instantiations
+            ),
+            HealthVerdict::LooksFine => write!(
+                f,
+                "looks fine ({} instantiations, longest chain {})",
+                self.instantiations, self.longest_chain
+            ),
+        }
+    }
+}
@@ -2,7 +2,7 @@
 use mem_dbg::{MemDbg, MemSize};
 
 use crate::{
-    items::{Fingerprint, InstIdx, Instantiation, Match, MatchIdx},
+    items::{ENodeIdx, Fingerprint, InstIdx, Instantiation, Match, MatchIdx},
     FxHashMap, Result, TiVec,
 };
 
@@ -13,6 +13,8 @@ pub struct Insts {
     fingerprint_to_match: FxHashMap<Fingerprint, (MatchIdx, Option<InstIdx>)>,
     pub(crate) matches: TiVec<MatchIdx, Match>,
     pub(crate) insts: TiVec<InstIdx, Instantiation>,
+    /// Reverse index of the trigger terms in `Match::blamed`.
+    blamed_by: FxHashMap<ENodeIdx, Vec<InstIdx>>,
 
     has_theory_solving_inst: bool,
 }
@@ -44,8 +46,18 @@ impl Insts {
             .fingerprint_to_match
             .get_mut(&fingerprint)
             .unwrap_or_else(|| panic!("{:x}", fingerprint.0));
+        let match_ = inst.match_;
         self.insts.raw.try_reserve(1)?;
         let idx = self.insts.push_and_get_key(inst);
+        for blame in self.matches[match_].trigger_matches() {
+            self.blamed_by.try_reserve(1)?;
+            let insts = self.blamed_by.entry(blame.enode()).or_default();
+            // The same enode may match multiple terms of one trigger.
+            if insts.last() != Some(&idx) {
+                insts.try_reserve(1)?;
+                insts.push(idx);
+            }
+        }
         debug_assert!(
             can_duplicate || inst_idx.is_none(),
             "duplicate fingerprint {fingerprint}"
@@ -54,6 +66,10 @@ impl Insts {
         Ok(idx)
     }
 
+    pub fn blamed_by(&self, enode: ENodeIdx) -> &[InstIdx] {
+        self.blamed_by.get(&enode).map_or(&[], Vec::as_slice)
+    }
+
     pub fn has_theory_solving_inst(&self) -> bool {
         self.has_theory_solving_inst
     }
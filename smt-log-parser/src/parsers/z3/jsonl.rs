@@ -0,0 +1,62 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::items::{MatchKind, QuantKind};
+
+use super::z3parser::Z3Parser;
+
+/// A line written by [`Z3Parser::stream_jsonl`].
+#[derive(Serialize)]
+struct InstLine {
+    idx: usize,
+    quant: String,
+    fingerprint: String,
+    line: usize,
+    cost: f64,
+    yields: Vec<String>,
+}
+
+impl Z3Parser {
+    /// Writes one JSON object per instantiation, each on its own line, in
+    /// the order the instantiations appear in the log:
+    ///
+    /// `{"idx":0,"quant":"loop","fingerprint":"0x1","line":27,"cost":3.5,"yields":["#19","#20"]}`
+    ///
+    /// `quant` is the name of the quantifier (or theory axiom id) that was
+    /// instantiated, `cost` is taken from
+    /// [`Z3Parser::instantiation_costs`] and
+    /// `yields` holds the ids of the terms of the enodes it created.
+    /// Lines are written as they are produced, so wrap `w` in a
+    /// [`BufWriter`](std::io::BufWriter) if it is unbuffered.
+    pub fn stream_jsonl(&self, mut w: impl Write) -> io::Result<()> {
+        let costs = self.instantiation_costs();
+        for (idx, inst) in self.instantiations() {
+            let quant = match &self[inst.match_].kind {
+                MatchKind::TheorySolving { axiom_id, .. } => self.term_id_name(*axiom_id),
+                MatchKind::MBQI { quant, .. }
+                | MatchKind::Axiom { axiom: quant, .. }
+                | MatchKind::Quantifier { quant, .. } => match self[*quant].kind {
+                    QuantKind::Other(name) | QuantKind::NamedQuant(name) => self[name].to_string(),
+                    QuantKind::Lambda => "<null>".to_string(),
+                    QuantKind::UnnamedQuant { name, id } => format!("{}!{id}", &self[name]),
+                },
+            };
+            let yields = inst.yields_terms.iter().map(|&enode| {
+                let term = &self[self[enode].owner];
+                term.id.map(|id| self.term_id_name(id)).unwrap_or_default()
+            });
+            let line = InstLine {
+                idx: usize::from(idx),
+                quant,
+                fingerprint: format!("0x{}", inst.fingerprint),
+                line: inst.line_no,
+                cost: costs[idx],
+                yields: yields.collect(),
+            };
+            serde_json::to_writer(&mut w, &line)?;
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
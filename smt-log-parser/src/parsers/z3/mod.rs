@@ -3,10 +3,23 @@ use std::fmt::Debug;
 use super::LogParser;
 use crate::{Error, FResult, Result};
 
+pub mod churn;
+pub mod cost;
+pub mod derivation;
 pub mod egraph;
+pub mod health;
 pub mod inst;
+#[cfg(feature = "serde")]
+pub mod jsonl;
+pub mod quant_stats;
+pub mod sexpr;
 pub mod stack;
+pub mod symbols;
+pub mod term_pattern;
 pub mod terms;
+#[cfg(feature = "timing")]
+pub mod timing;
+pub mod validate;
 /// Original Z3 log parser. Works with Z3 v.4.12.1, should work with other versions
 /// as long as the log format is the same for the important line cases.
 /// Compare with the log files in the `logs/` folder to see if this is the case.
@@ -17,13 +30,17 @@ impl<T: Z3LogParser + Default> LogParser for T {
         first_byte == b'['
     }
 
+    fn is_entry_complete(&mut self, entry: &str) -> bool {
+        entry.bytes().fold(None, in_quote).is_none()
+    }
+
     fn process_line(&mut self, line: &str, line_no: usize) -> FResult<bool> {
-        // Much faster than `split_whitespace` or `split(' ')` since it works on
-        // [u8] instead of [char] and so doesn't need to convert to UTF-8.
-        let mut split = line.split_ascii_whitespace();
+        let mut split = split_fields(line);
         let Some(first) = split.next() else {
             return Ok(true);
         };
+        #[cfg(feature = "timing")]
+        let start = wasm_timer::Instant::now();
         let parse = match first {
             // match the line case
             "[tool-version]" => self.version_info(split),
@@ -33,11 +50,11 @@ impl<T: Z3LogParser + Default> LogParser for T {
             "[mk-app]" => self.mk_proof_app(split, false),
             "[attach-meaning]" => self.attach_meaning(split),
             "[attach-var-names]" => self.attach_var_names(split),
-            "[attach-enode]" => self.attach_enode(split),
+            "[attach-enode]" => self.attach_enode(split, line_no),
             "[eq-expl]" => self.eq_expl(split),
             "[new-match]" => self.new_match(split),
             "[inst-discovered]" => self.inst_discovered(split),
-            "[instance]" => self.instance(split),
+            "[instance]" => self.instance(split, line_no),
             "[end-of-instance]" => self.end_of_instance(split),
             "[decide-and-or]" => self.decide_and_or(split),
             "[decide]" => self.decide(split),
@@ -52,6 +69,8 @@ impl<T: Z3LogParser + Default> LogParser for T {
             "[conflict]" => self.conflict(split),
             _ => Err(Error::UnknownLine(first.to_owned())),
         };
+        #[cfg(feature = "timing")]
+        self.timing_mut().record(first, start);
         match parse {
             Ok(()) => Ok(true),
             Err(err) => {
@@ -73,6 +92,43 @@ impl<T: Z3LogParser + Default> LogParser for T {
     }
 }
 
+/// Steps the state of whether we are inside of a `|...|` quoted symbol or a
+/// `"..."` string literal, given by the opening byte, over the byte `b`.
+/// Escaped quotes in string literals are written as `""` and so toggle the
+/// state twice.
+fn in_quote(quote: Option<u8>, b: u8) -> Option<u8> {
+    match quote {
+        None if b == b'|' || b == b'"' => Some(b),
+        Some(q) if b == q => None,
+        quote => quote,
+    }
+}
+
+/// Splits an entry of the log at ASCII whitespace, except for whitespace
+/// (including newlines) within quoted symbols and string literals. Much
+/// faster than `split_whitespace` since it works on [u8] instead of [char]
+/// and so doesn't need to convert to UTF-8.
+fn split_fields(line: &str) -> impl Iterator<Item = &str> {
+    let mut rest = line;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        if rest.is_empty() {
+            return None;
+        }
+        let mut quote = None;
+        let end = rest
+            .bytes()
+            .position(|b| {
+                quote = in_quote(quote, b);
+                quote.is_none() && b.is_ascii_whitespace()
+            })
+            .unwrap_or(rest.len());
+        let (field, tail) = rest.split_at(end);
+        rest = tail;
+        Some(field)
+    })
+}
+
 const DEFAULT: Result<()> = Ok(());
 pub trait Z3LogParser {
     /* Methods to handle each line case of Z3 logs.
@@ -85,15 +141,17 @@ pub trait Z3LogParser {
     fn mk_proof_app<'a>(&mut self, l: impl Iterator<Item = &'a str>, is_proof: bool) -> Result<()>;
     fn attach_meaning<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
     fn attach_var_names<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
-    fn attach_enode<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
+    fn attach_enode<'a>(&mut self, l: impl Iterator<Item = &'a str>, line_no: usize) -> Result<()>;
     fn eq_expl<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
     fn new_match<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
     fn inst_discovered<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
-    fn instance<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
+    fn instance<'a>(&mut self, l: impl Iterator<Item = &'a str>, line_no: usize) -> Result<()>;
     fn end_of_instance<'a>(&mut self, l: impl Iterator<Item = &'a str>) -> Result<()>;
     fn push<'a>(&mut self, _l: impl Iterator<Item = &'a str>) -> Result<()>;
     fn pop<'a>(&mut self, _l: impl Iterator<Item = &'a str>) -> Result<()>;
     fn eof(&mut self);
+    #[cfg(feature = "timing")]
+    fn timing_mut(&mut self) -> &mut timing::ParseTiming;
 
     // unused in original parser
     fn decide_and_or<'a>(&mut self, _l: impl Iterator<Item = &'a str>) -> Result<()> {
@@ -0,0 +1,80 @@
+use crate::{
+    items::{InstIdx, MatchKind, QuantIdx, TermId},
+    FxHashMap, TiVec,
+};
+
+use super::z3parser::Z3Parser;
+
+impl Z3Parser {
+    /// The quantifiers which were instantiated, but only ever by MBQI (see
+    /// [`MatchKind::is_mbqi`]), in order. Z3 never found a match for their
+    /// triggers, which often means that the triggers are badly chosen, and
+    /// adding explicit triggers can improve performance considerably.
+    pub fn mbqi_only_quantifiers(&self) -> Vec<QuantIdx> {
+        // Whether each quantifier was instantiated by MBQI and otherwise.
+        let mut kinds: TiVec<QuantIdx, (bool, bool)> =
+            self.quantifiers.iter().map(|_| (false, false)).collect();
+        for (_, inst) in self.instantiations() {
+            let kind = &self[inst.match_].kind;
+            let Some(quant) = kind.quant_idx() else {
+                continue;
+            };
+            if kind.is_mbqi() {
+                kinds[quant].0 = true;
+            } else {
+                kinds[quant].1 = true;
+            }
+        }
+        kinds
+            .iter_enumerated()
+            .filter(|(_, &(mbqi, other))| mbqi && !other)
+            .map(|(quant, _)| quant)
+            .collect()
+    }
+
+    /// The average number of terms yielded per instantiation of each
+    /// quantifier which was instantiated at least once, most productive
+    /// first. Quantifiers which are both instantiated often and productive
+    /// are likely to be fuelling a matching loop.
+    pub fn quant_productivity(&self) -> Vec<(QuantIdx, f32)> {
+        let mut counts: TiVec<QuantIdx, (usize, usize)> =
+            self.quantifiers.iter().map(|_| (0, 0)).collect();
+        for (_, inst) in self.instantiations() {
+            let Some(quant) = self[inst.match_].kind.quant_idx() else {
+                continue;
+            };
+            counts[quant].0 += 1;
+            counts[quant].1 += inst.yields_terms.len();
+        }
+        let mut productivity: Vec<_> = counts
+            .iter_enumerated()
+            .filter(|(_, &(insts, _))| insts > 0)
+            .map(|(quant, &(insts, yields))| (quant, yields as f32 / insts as f32))
+            .collect();
+        productivity.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        productivity
+    }
+
+    /// Theory solving instantiations grouped by the id of the theory axiom
+    /// they instantiated, with the largest groups first. Use
+    /// [`Z3Parser::term_id_name`] for a readable name of each axiom.
+    pub fn theory_axioms(&self) -> Vec<(TermId, Vec<InstIdx>)> {
+        let mut groups = FxHashMap::<TermId, Vec<InstIdx>>::default();
+        for (idx, inst) in self.instantiations() {
+            if let MatchKind::TheorySolving { axiom_id, .. } = &self[inst.match_].kind {
+                groups.entry(*axiom_id).or_default().push(idx);
+            }
+        }
+        let mut groups: Vec<_> = groups
+            .iter()
+            .map(|(&id, insts)| (id, insts.clone()))
+            .collect();
+        groups.sort_by(|a, b| {
+            b.1.len()
+                .cmp(&a.1.len())
+                .then_with(|| self[a.0.namespace].cmp(&self[b.0.namespace]))
+                .then_with(|| a.0.id.cmp(&b.0.id))
+        });
+        groups
+    }
+}
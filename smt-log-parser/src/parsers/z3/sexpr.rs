@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::items::{QuantKind, Quantifier, TermIdx, TermKind, VarNames};
+
+use super::z3parser::Z3Parser;
+
+/// The characters other than letters and digits allowed in a simple SMT-LIB
+/// symbol.
+const SIMPLE_SYMBOL_CHARS: &str = "~!@$%^&*_-+=<>.?/";
+
+/// Quotes `name` so that it is read back as a single SMT-LIB symbol. Simple
+/// symbols are returned as is, as are names which Z3 already logged as a
+/// quoted `|...|` symbol. Anything else is wrapped in `|...|`; since SMT-LIB
+/// does not allow `|` or `\` within quoted symbols these are escaped with a
+/// `\`, which Z3 accepts but other solvers may not.
+pub fn quote_symbol(name: &str) -> Cow<'_, str> {
+    let is_simple = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || SIMPLE_SYMBOL_CHARS.contains(c));
+    let is_quoted = name.len() >= 2
+        && name.starts_with('|')
+        && name.ends_with('|')
+        && !name[1..name.len() - 1].contains(['|', '\\']);
+    if is_simple || is_quoted {
+        return Cow::Borrowed(name);
+    }
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push('|');
+    for c in name.chars() {
+        if matches!(c, '|' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('|');
+    Cow::Owned(quoted)
+}
+
+impl Z3Parser {
+    /// Exports `t` as an SMT-LIB s-expression, quoting symbols with
+    /// [`quote_symbol`]. Unlike the infix pretty printer this ignores
+    /// meanings and the display configuration. Bound variables without a
+    /// logged name are shown as `qvar_{idx}` and patterns of quantifiers are
+    /// dropped.
+    pub fn term_sexpr(&self, t: TermIdx) -> String {
+        let mut sexpr = String::new();
+        self.write_sexpr(t, &mut Vec::new(), &mut sexpr);
+        sexpr
+    }
+
+    fn write_sexpr<'a>(&'a self, t: TermIdx, quants: &mut Vec<&'a Quantifier>, out: &mut String) {
+        let term = &self[t];
+        match term.kind {
+            TermKind::Var(mut idx) => {
+                // Resolve the de Bruijn index, innermost quantifier first.
+                let quant = quants.iter().rev().find(|q| {
+                    let found = q.num_vars > idx;
+                    if !found {
+                        idx -= q.num_vars;
+                    }
+                    found
+                });
+                match quant.and_then(|q| q.vars.as_ref()) {
+                    Some(VarNames::NameAndType(names)) => {
+                        out.push_str(&quote_symbol(&self[names[idx].0]))
+                    }
+                    _ => write!(out, "qvar_{idx}").unwrap(),
+                }
+            }
+            TermKind::Generalised => out.push('_'),
+            TermKind::ProofOrApp(app) => {
+                let name = quote_symbol(&self[app.name]);
+                if term.child_ids.is_empty() {
+                    out.push_str(&name);
+                    return;
+                }
+                write!(out, "({name}").unwrap();
+                for &child in term.child_ids.iter() {
+                    out.push(' ');
+                    self.write_sexpr(child, quants, out);
+                }
+                out.push(')');
+            }
+            TermKind::Quant(q) => {
+                let quant = &self[q];
+                let binder = match quant.kind {
+                    QuantKind::Lambda => "lambda",
+                    _ => "forall",
+                };
+                write!(out, "({binder} (").unwrap();
+                for idx in 0..quant.num_vars {
+                    if idx != 0 {
+                        out.push(' ');
+                    }
+                    let name = match &quant.vars {
+                        Some(VarNames::NameAndType(names)) => quote_symbol(&self[names[idx].0]),
+                        _ => Cow::Owned(format!("qvar_{idx}")),
+                    };
+                    let ty = match &quant.vars {
+                        Some(VarNames::NameAndType(names)) => Some(names[idx].1),
+                        Some(VarNames::TypeOnly(types)) => Some(types[idx]),
+                        None => None,
+                    };
+                    let ty = ty.map_or(Cow::Borrowed("?"), |ty| quote_symbol(&self[ty]));
+                    write!(out, "({name} {ty})").unwrap();
+                }
+                out.push_str(") ");
+                quants.push(quant);
+                // The body is the last child, the others are patterns.
+                self.write_sexpr(*term.child_ids.last().unwrap(), quants, out);
+                quants.pop();
+                out.push(')');
+            }
+        }
+    }
+}
@@ -57,6 +57,10 @@ impl Stack {
         res
     }
 
+    pub(super) fn height(&self) -> usize {
+        self.stack.len()
+    }
+
     pub(super) fn active_frame(&self) -> Option<StackIdx> {
         self.stack.last().copied()
     }
@@ -0,0 +1,67 @@
+use fxhash::FxHashSet;
+
+use crate::{
+    items::{InstIdx, TermIdx},
+    FxHashMap, IString,
+};
+
+use super::z3parser::Z3Parser;
+
+impl Z3Parser {
+    /// Whether `t` or any of its subterms is an application of `symbol`.
+    /// Results are cached in `memo` for every subterm visited, so a `memo`
+    /// reused across calls walks each shared subterm only once. `None` on a
+    /// [`Z3Parser::new_summary`] parser since term arguments are not kept.
+    pub fn contains_symbol(
+        &self,
+        t: TermIdx,
+        symbol: IString,
+        memo: &mut FxHashMap<TermIdx, bool>,
+    ) -> Option<bool> {
+        if self.summary {
+            return None;
+        }
+        // Explicit stack rather than recursion since terms can be very deep.
+        let mut stack = vec![t];
+        while let Some(&next) = stack.last() {
+            if memo.contains_key(&next) {
+                stack.pop();
+                continue;
+            }
+            let term = &self[next];
+            let missing = stack.len();
+            stack.extend(term.child_ids.iter().filter(|c| !memo.contains_key(c)));
+            if stack.len() != missing {
+                continue;
+            }
+            stack.pop();
+            let contains = term.kind.app_name() == Some(symbol)
+                || term.child_ids.iter().any(|child| memo[child]);
+            memo.insert(next, contains);
+        }
+        Some(memo[&t])
+    }
+
+    /// All instantiations where one of the bound terms, the resulting term
+    /// or a yielded term contains an application of the function symbol
+    /// `name`, see [`Z3Parser::contains_symbol`]. Empty if `name` never
+    /// appeared in the log, `None` on a [`Z3Parser::new_summary`] parser.
+    pub fn insts_containing_symbol(&self, name: &str) -> Option<FxHashSet<InstIdx>> {
+        if self.summary {
+            return None;
+        }
+        let Some(symbol) = self.strings.get(name).map(IString) else {
+            return Some(Default::default());
+        };
+        let mut memo = FxHashMap::default();
+        let insts = self.instantiations().filter(|(_, inst)| {
+            let mut terms = self[inst.match_].kind.bound_terms(|e| self[e].owner, |t| t);
+            terms.extend(inst.get_resulting_term());
+            terms.extend(inst.yields_terms.iter().map(|&e| self[e].owner));
+            terms
+                .into_iter()
+                .any(|t| self.contains_symbol(t, symbol, &mut memo) == Some(true))
+        });
+        Some(insts.map(|(idx, _)| idx).collect())
+    }
+}
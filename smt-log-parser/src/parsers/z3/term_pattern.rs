@@ -0,0 +1,140 @@
+use std::{fmt, iter::Peekable, str::FromStr};
+
+use crate::{
+    items::{TermIdx, TermKind},
+    FxHashMap,
+};
+
+use super::z3parser::Z3Parser;
+
+/// A structural pattern over terms written as an s-expression, e.g.
+/// `(f ?x (g ?x))`, see [`Z3Parser::find_terms_matching`]. A symbol matches
+/// an application of that function symbol to arguments matching the
+/// argument patterns, a `?name` variable matches any term. All occurrences
+/// of the same variable must match the same term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermPattern {
+    Var(String),
+    App(String, Vec<TermPattern>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermPatternError {
+    UnexpectedEnd,
+    UnexpectedClose,
+    /// An opening parenthesis not followed by a function symbol, e.g.
+    /// `(?f x)` or `((f) x)`.
+    ExpectedFunction(String),
+    /// Input left over after the first complete pattern.
+    TrailingInput(String),
+}
+
+impl fmt::Display for TermPatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of pattern"),
+            Self::UnexpectedClose => write!(f, "unexpected `)`"),
+            Self::ExpectedFunction(found) => {
+                write!(f, "expected a function symbol, found `{found}`")
+            }
+            Self::TrailingInput(found) => write!(f, "unexpected `{found}` after pattern"),
+        }
+    }
+}
+
+impl FromStr for TermPattern {
+    type Err = TermPatternError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = tokens(s).peekable();
+        let pattern = Self::parse(&mut tokens)?;
+        match tokens.next() {
+            None => Ok(pattern),
+            Some(token) => Err(TermPatternError::TrailingInput(token.to_string())),
+        }
+    }
+}
+
+impl TermPattern {
+    fn parse<'a>(
+        tokens: &mut Peekable<impl Iterator<Item = &'a str>>,
+    ) -> Result<Self, TermPatternError> {
+        match tokens.next().ok_or(TermPatternError::UnexpectedEnd)? {
+            "(" => {
+                let name = tokens.next().ok_or(TermPatternError::UnexpectedEnd)?;
+                if matches!(name, "(" | ")") || name.starts_with('?') {
+                    return Err(TermPatternError::ExpectedFunction(name.to_string()));
+                }
+                let mut args = Vec::new();
+                while tokens.peek().ok_or(TermPatternError::UnexpectedEnd)? != &")" {
+                    args.push(Self::parse(tokens)?);
+                }
+                tokens.next();
+                Ok(Self::App(name.to_string(), args))
+            }
+            ")" => Err(TermPatternError::UnexpectedClose),
+            var if var.starts_with('?') => Ok(Self::Var(var[1..].to_string())),
+            name => Ok(Self::App(name.to_string(), Vec::new())),
+        }
+    }
+}
+
+/// Splits `s` into parentheses and symbols, keeping `|...|` quoted symbols
+/// (which may contain whitespace and parentheses) whole.
+fn tokens(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start();
+        let len = match rest.chars().next()? {
+            '(' | ')' => 1,
+            '|' => rest[1..].find('|').map_or(rest.len(), |end| end + 2),
+            _ => rest
+                .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                .unwrap_or(rest.len()),
+        };
+        let (token, tail) = rest.split_at(len);
+        rest = tail;
+        Some(token)
+    })
+}
+
+impl Z3Parser {
+    /// All terms of the log which match `pattern`, in the order they were
+    /// created. Quantifiers and bound variables are never matched by a
+    /// symbol, only by a `?` variable. `None` on a [`Z3Parser::new_summary`]
+    /// parser since term arguments are not kept.
+    pub fn find_terms_matching(&self, pattern: &TermPattern) -> Option<Vec<TermIdx>> {
+        if self.is_summary() {
+            return None;
+        }
+        let terms = self.terms.parsed_term_idxs();
+        Some(
+            terms
+                .filter(|&t| self.matches_pattern(t, pattern, &mut FxHashMap::default()))
+                .collect(),
+        )
+    }
+
+    fn matches_pattern<'p>(
+        &self,
+        t: TermIdx,
+        pattern: &'p TermPattern,
+        bound: &mut FxHashMap<&'p str, TermIdx>,
+    ) -> bool {
+        match pattern {
+            TermPattern::Var(var) => *bound.entry(var.as_str()).or_insert(t) == t,
+            TermPattern::App(name, args) => {
+                let term = &self[t];
+                let TermKind::ProofOrApp(app) = &term.kind else {
+                    return false;
+                };
+                self[app.name] == **name
+                    && term.child_ids.len() == args.len()
+                    && term
+                        .child_ids
+                        .iter()
+                        .zip(args)
+                        .all(|(&child, arg)| self.matches_pattern(child, arg, bound))
+            }
+        }
+    }
+}
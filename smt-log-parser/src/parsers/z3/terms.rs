@@ -4,7 +4,7 @@ use mem_dbg::{MemDbg, MemSize};
 use crate::{
     error::Either,
     items::{Meaning, QuantIdx, Term, TermAndMeaning, TermId, TermIdToIdxMap, TermIdx, TermKind},
-    Error, FxHashMap, Result, StringTable, TiVec,
+    Error, FxHashMap, IString, Result, StringTable, TiVec,
 };
 
 #[cfg_attr(feature = "mem_dbg", derive(MemSize, MemDbg))]
@@ -13,6 +13,8 @@ pub struct Terms {
     term_id_map: TermIdToIdxMap,
     terms: TiVec<TermIdx, Term>,
     meanings: FxHashMap<TermIdx, Meaning>,
+    /// Meanings attached to an id before the term with that id was created.
+    pending_meanings: FxHashMap<TermId, Meaning>,
     parsed_terms: Option<TermIdx>,
 
     synthetic_terms: FxHashMap<TermAndMeaning<'static>, TermIdx>,
@@ -24,6 +26,7 @@ impl Terms {
             term_id_map: TermIdToIdxMap::new(strings),
             terms: TiVec::default(),
             meanings: FxHashMap::default(),
+            pending_meanings: FxHashMap::default(),
             parsed_terms: None,
 
             synthetic_terms: FxHashMap::default(),
@@ -36,6 +39,9 @@ impl Terms {
         let idx = self.terms.push_and_get_key(term);
         if let Some(id) = id {
             self.term_id_map.register_term(id, idx)?;
+            if let Some(meaning) = self.pending_meanings.remove(&id) {
+                self.new_meaning(idx, meaning)?;
+            }
         }
         Ok(idx)
     }
@@ -68,15 +74,17 @@ impl Terms {
             .ok_or_else(|| Error::UnknownQuantifierIdx(quant))
     }
 
+    /// Attaches a meaning to a term, replacing any previous meaning.
     pub(super) fn new_meaning(&mut self, term: TermIdx, meaning: Meaning) -> Result<()> {
         self.meanings.try_reserve(1)?;
-        use std::collections::hash_map::Entry;
-        match self.meanings.entry(term) {
-            Entry::Occupied(old) => assert_eq!(old.get(), &meaning),
-            Entry::Vacant(empty) => {
-                empty.insert(meaning);
-            }
-        };
+        self.meanings.insert(term, meaning);
+        Ok(())
+    }
+    /// Holds on to a meaning attached to an id which has no term yet, it is
+    /// attached once the term is created. A later meaning replaces this one.
+    pub(super) fn new_pending_meaning(&mut self, id: TermId, meaning: Meaning) -> Result<()> {
+        self.pending_meanings.try_reserve(1)?;
+        self.pending_meanings.insert(id, meaning);
         Ok(())
     }
 
@@ -87,6 +95,19 @@ impl Terms {
         }
     }
 
+    /// The terms created while parsing, i.e. without the synthetic terms
+    /// created during analysis.
+    pub(super) fn parsed_terms(&self) -> impl Iterator<Item = &Term> {
+        let end = self.parsed_terms.unwrap_or(self.terms.next_key());
+        self.terms.iter().take(usize::from(end))
+    }
+
+    /// The indices of [`parsed_terms`](Self::parsed_terms).
+    pub(super) fn parsed_term_idxs(&self) -> impl Iterator<Item = TermIdx> + '_ {
+        let end = self.parsed_terms.unwrap_or(self.terms.next_key());
+        self.terms.keys().take(usize::from(end))
+    }
+
     pub(super) fn end_of_file(&mut self) {
         self.parsed_terms = Some(self.terms.next_key());
     }
@@ -139,3 +160,15 @@ impl std::ops::IndexMut<TermIdx> for Terms {
         &mut self.terms[idx]
     }
 }
+
+/// Summary of the terms in a log, see [`Z3Parser::term_stats`](super::z3parser::Z3Parser::term_stats).
+#[derive(Debug, Clone, Default)]
+pub struct TermStats {
+    /// Terms are hash-consed so every term is distinct.
+    pub distinct_terms: usize,
+    /// The number of references from terms to their children.
+    pub total_children: usize,
+    /// How many distinct applications there are of each function symbol,
+    /// most frequent first.
+    pub top_symbols: Vec<(IString, usize)>,
+}
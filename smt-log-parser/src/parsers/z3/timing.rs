@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use wasm_timer::Instant;
+
+/// Time spent by the parser itself in each phase of processing a log, only
+/// collected with the `timing` feature enabled. Retrieve it with
+/// [`Z3Parser::timing`](super::z3parser::Z3Parser::timing).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseTiming {
+    /// Time spent between handling lines: reading the input and splitting it
+    /// into lines. If parsing was paused in between lines (e.g. with
+    /// `process_until`), the pause is counted here as well.
+    pub tokenizing: Duration,
+    /// `[mk-*]` and `[attach-*]` lines.
+    pub terms: Duration,
+    /// `[eq-expl]` lines.
+    pub equalities: Duration,
+    /// `[new-match]`, `[inst-discovered]`, `[instance]` and
+    /// `[end-of-instance]` lines.
+    pub instantiations: Duration,
+    /// All remaining lines.
+    pub other: Duration,
+    last_line_end: Option<Instant>,
+}
+
+impl ParseTiming {
+    /// Records a line starting with `first` whose handling began at `start`
+    /// and has just finished.
+    pub(crate) fn record(&mut self, first: &str, start: Instant) {
+        let end = Instant::now();
+        if let Some(last_line_end) = self.last_line_end {
+            self.tokenizing += start - last_line_end;
+        }
+        let phase = match first {
+            "[mk-quant]" | "[mk-lambda]" | "[mk-var]" | "[mk-proof]" | "[mk-app]"
+            | "[attach-meaning]" | "[attach-var-names]" | "[attach-enode]" => &mut self.terms,
+            "[eq-expl]" => &mut self.equalities,
+            "[new-match]" | "[inst-discovered]" | "[instance]" | "[end-of-instance]" => {
+                &mut self.instantiations
+            }
+            _ => &mut self.other,
+        };
+        *phase += end - start;
+        self.last_line_end = Some(end);
+    }
+
+    pub fn total(&self) -> Duration {
+        self.tokenizing + self.terms + self.equalities + self.instantiations + self.other
+    }
+}
@@ -0,0 +1,130 @@
+use crate::items::{
+    ENodeIdx, EqGivenIdx, EqTransIdx, Fingerprint, InstIdx, MatchKind, TermId, TermIdx,
+};
+
+use super::z3parser::Z3Parser;
+
+/// An invariant of a parsed log which did not hold, found by
+/// [`Z3Parser::validate`]. These point to either a bug in the parser or an
+/// unusual trace.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationWarning {
+    /// The instantiation blames an enode which was attached on a later line
+    /// than the `[instance]` line.
+    EnodeAfterInst {
+        inst: InstIdx,
+        enode: ENodeIdx,
+        enode_line: usize,
+        inst_line: usize,
+    },
+    /// The instantiation blames an enode created by itself or by an
+    /// instantiation which came after it.
+    EnodeCreatedLater {
+        inst: InstIdx,
+        enode: ENodeIdx,
+        created_by: InstIdx,
+    },
+    /// The instantiation blames an equality which does not exist.
+    UnknownBlamedEquality { inst: InstIdx, eq: EqTransIdx },
+    /// An endpoint of the equality explanation is not a known enode.
+    UnknownEqualityEndpoint { eq: EqGivenIdx, enode: ENodeIdx },
+    /// The proof id of the instantiation was never defined by a term.
+    UnresolvedProofId { inst: InstIdx, id: TermId },
+}
+
+impl Z3Parser {
+    /// Cross-checks the parsed data for consistency, returning a warning for
+    /// each violation found. This does not modify the parser and is not run
+    /// during parsing; it is meant as a diagnostic (e.g. `test --validate`).
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        let enodes = self.egraph.enodes.len();
+        let equalities = &self.egraph.equalities;
+        for (inst, data) in self.instantiations() {
+            let match_ = &self[data.match_];
+            for blame in match_.trigger_matches() {
+                let enode = blame.enode();
+                let node = &self[enode];
+                if let Some(enode_line) = node.line_no.filter(|&l| l > data.line_no) {
+                    warnings.push(ValidationWarning::EnodeAfterInst {
+                        inst,
+                        enode,
+                        enode_line,
+                        inst_line: data.line_no,
+                    });
+                }
+                if let Some(created_by) = node.created_by.filter(|&c| c >= inst) {
+                    warnings.push(ValidationWarning::EnodeCreatedLater {
+                        inst,
+                        enode,
+                        created_by,
+                    });
+                }
+                for eq in blame.equalities() {
+                    if equalities.transitive.get(eq).is_none() {
+                        warnings.push(ValidationWarning::UnknownBlamedEquality { inst, eq });
+                    }
+                }
+            }
+            if let Some(id) = data.proof_id.as_ref().and_then(|p| p.as_result().err()) {
+                warnings.push(ValidationWarning::UnresolvedProofId { inst, id: *id });
+            }
+        }
+        for (eq, expl) in equalities.given.iter_enumerated() {
+            let (from, to) = (expl.from(), expl.to());
+            for enode in std::iter::once(from).chain((to != from).then_some(to)) {
+                if usize::from(enode) >= enodes {
+                    warnings.push(ValidationWarning::UnknownEqualityEndpoint { eq, enode });
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Groups of instantiations which share both their fingerprint and their
+    /// match (the same quantifier instantiated with the same bound terms).
+    /// Z3 should not instantiate the same match twice, so any group found
+    /// points to a misconfigured solver or a broken trace. Groups are in the
+    /// order of their first instantiation.
+    pub fn duplicate_instantiations(&self) -> Vec<(Fingerprint, Vec<InstIdx>)> {
+        let mut groups = fxhash::FxHashMap::<(Fingerprint, &MatchKind), Vec<InstIdx>>::default();
+        for (inst, data) in self.instantiations() {
+            let key = (data.fingerprint, &self[data.match_].kind);
+            groups.entry(key).or_default().push(inst);
+        }
+        let mut duplicates: Vec<_> = groups
+            .into_iter()
+            .filter(|(_, insts)| insts.len() > 1)
+            .map(|((fingerprint, _), insts)| (fingerprint, insts))
+            .collect();
+        duplicates.sort_by_key(|(_, insts)| insts[0]);
+        duplicates
+    }
+
+    /// Groups of instantiations of the same quantifier whose bound terms are
+    /// pairwise equal in the e-graph, even if they are distinct terms with
+    /// different fingerprints. All but one instantiation of each group are
+    /// redundant work. Bound enodes are compared by their
+    /// [`root`](super::egraph::EGraph::root) in the e-graph at the end of the
+    /// trace. Groups are in the order of their first instantiation.
+    pub fn semantically_duplicate_instantiations(&self) -> Vec<Vec<InstIdx>> {
+        let mut groups = fxhash::FxHashMap::<_, Vec<InstIdx>>::default();
+        for (inst, data) in self.instantiations() {
+            let kind = &self[data.match_].kind;
+            let Some(quant) = kind.quant_idx() else {
+                continue;
+            };
+            // An enode whose root cannot be found is only equal to itself.
+            let root = |e| self.egraph.root(e, &self.stack).unwrap_or(e);
+            let bound: Vec<std::result::Result<ENodeIdx, TermIdx>> =
+                kind.bound_terms(|e| Ok(root(e)), Err);
+            groups.entry((quant, bound)).or_default().push(inst);
+        }
+        let mut duplicates: Vec<_> = groups
+            .into_values()
+            .filter(|insts| insts.len() > 1)
+            .collect();
+        duplicates.sort_by_key(|insts| insts[0]);
+        duplicates
+    }
+}
@@ -1,17 +1,35 @@
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::{MemDbg, MemSize};
+use std::{
+    fs::Metadata,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use petgraph::graph::{DiGraph, NodeIndex};
 
+#[cfg(feature = "display")]
 use crate::{
+    display_with::{DisplayConfiguration, DisplayCtxt, DisplayWithCtxt, NameNormalizer},
+    formatter::TermDisplayContext,
+};
+use crate::{
+    error::Either,
     items::*,
-    parsers::z3::{VersionInfo, Z3LogParser},
-    Error, IString, Result, StringTable, TiVec,
+    parsers::{
+        z3::{VersionInfo, Z3LogParser},
+        FileRead, StreamParser,
+    },
+    BoxSlice, Error, FxHashMap, IString, Result, StringTable, TiVec,
 };
 
+#[cfg(feature = "timing")]
+use super::timing::ParseTiming;
 use super::{
     egraph::{EGraph, ENode},
     inst::Insts,
     stack::Stack,
-    terms::Terms,
+    terms::{TermStats, Terms},
 };
 
 /// A parser for Z3 log files. Use one of the various `Z3Parser::from_*` methods
@@ -26,27 +44,117 @@ pub struct Z3Parser {
 
     pub(crate) insts: Insts,
     pub(crate) inst_stack: Vec<(InstIdx, Vec<ENodeIdx>)>,
+    /// Instantiations whose proof term was not yet created at their
+    /// `[instance]` line, keyed by the id of that proof term.
+    pub(crate) pending_proofs: FxHashMap<TermId, Vec<InstIdx>>,
 
     pub(crate) egraph: EGraph,
     pub(crate) stack: Stack,
+    /// The clause of each `[conflict]` line, in order.
+    pub(crate) conflicts: Vec<BoxSlice<ConflictLit>>,
 
     pub strings: StringTable,
+    /// See [`Z3Parser::new_summary`].
+    pub(crate) summary: bool,
+    /// See [`Z3Parser::new_without_equalities`].
+    pub(crate) skip_equalities: bool,
+
+    #[cfg(feature = "timing")]
+    pub(crate) timing: ParseTiming,
 }
 
 impl Default for Z3Parser {
     fn default() -> Self {
-        let mut strings = StringTable::with_hasher(fxhash::FxBuildHasher::default());
+        Self::with_strings(StringTable::with_hasher(fxhash::FxBuildHasher::default()))
+    }
+}
+
+impl Z3Parser {
+    /// Creates a new parser which interns into an existing string table
+    /// rather than a fresh one. Interning only ever appends to the table, so
+    /// every [`IString`] handed out by the table before this call stays valid
+    /// and compares equal to the same string interned by this parser. Use this
+    /// to parse several related traces one after the other (e.g. passing
+    /// `first.strings.clone()` on to the parser of the second trace) so that
+    /// their names can be compared directly by `IString`. Combine with
+    /// [`StreamParser::with_parser`](crate::parsers::StreamParser::with_parser)
+    /// to parse from a reader.
+    ///
+    /// Cloning a [`StringTable`] is cheap and the first parser keeps working
+    /// with its clone. The parsers share the strings until one of them
+    /// interns a new string, which then gets a copy of the table of its own.
+    /// So share a table only once the parser which filled it is done, as
+    /// strings interned by both parsers afterwards may get the same
+    /// `IString`. The table is shared through an `Rc`, so the parsers cannot
+    /// be sent to other threads; to parse traces concurrently, give each its
+    /// own table.
+    pub fn with_strings(mut strings: StringTable) -> Self {
         Self {
             version_info: VersionInfo::default(),
             terms: Terms::new(&mut strings),
             quantifiers: Default::default(),
             insts: Default::default(),
             inst_stack: Default::default(),
+            pending_proofs: Default::default(),
             egraph: Default::default(),
             stack: Default::default(),
+            conflicts: Default::default(),
             strings,
+            summary: false,
+            skip_equalities: false,
+            #[cfg(feature = "timing")]
+            timing: Default::default(),
         }
     }
+
+    /// Creates a parser which keeps the structure of the log (quantifiers,
+    /// instantiations, enodes and equalities) but drops the bodies of terms:
+    /// apps and proof steps are stored with their id and name only, and
+    /// meanings are not stored at all. This cuts memory usage significantly
+    /// for large logs. Quantifier terms are still kept in full.
+    ///
+    /// Features which depend on term contents return `None` on such a parser
+    /// (e.g. [`Z3Parser::structural_hash`], [`Z3Parser::term_stats`] and
+    /// [`Z3Parser::meaning`]), and terms display without their arguments.
+    pub fn new_summary() -> Self {
+        Self {
+            summary: true,
+            ..Self::default()
+        }
+    }
+
+    /// Streams the log at `p` into a [`Z3Parser::new_summary`] parser, see
+    /// [`LogParser::from_file`](crate::LogParser::from_file).
+    pub fn from_file_summary<P: AsRef<Path>>(
+        p: P,
+    ) -> std::io::Result<(Metadata, StreamParser<'static, Self>)> {
+        let (meta, reader) = p.read_open()?;
+        Ok((meta, StreamParser::with_parser(reader, Self::new_summary())))
+    }
+
+    /// Whether this parser was created with [`Z3Parser::new_summary`].
+    pub fn is_summary(&self) -> bool {
+        self.summary
+    }
+
+    /// Creates a parser which skips the `[eq-expl]` lines and does not build
+    /// any transitive equalities, saving the time and memory these take for
+    /// users only interested in instantiations and the terms they blame and
+    /// yield. Equalities used by a match are kept only as a
+    /// [`BlameKind::UnknownEquality`] between the two enodes, so neither the
+    /// equalities nor the edges from them show up in the instantiation graph.
+    pub fn new_without_equalities() -> Self {
+        Self {
+            skip_equalities: true,
+            ..Self::default()
+        }
+    }
+
+    /// Whether this parser was created with
+    /// [`Z3Parser::new_without_equalities`].
+    pub fn skips_equalities(&self) -> bool {
+        self.skip_equalities
+    }
 }
 
 impl Z3Parser {
@@ -56,7 +164,7 @@ impl Z3Parser {
         if self.version_info.is_version(4, 12, 2) && enode.is_err() {
             // Very rarely in version 4.12.2, an `[attach-enode]` is not emitted. Create it here.
             // TODO: log somewhere when this happens.
-            self.egraph.new_enode(None, idx, None, &self.stack)?;
+            self.egraph.new_enode(None, idx, None, None, &self.stack)?;
             return self.egraph.get_enode(idx, &self.stack);
         }
         enode
@@ -231,7 +339,7 @@ impl Z3LogParser for Z3Parser {
         // Return if there is unexpectedly more data
         Self::expect_completed(l)?;
         let version = semver::Version::parse(version)?;
-        println!("{solver} {version}");
+        eprintln!("{solver} {version}");
         self.version_info = VersionInfo::Present { solver, version };
         Ok(())
     }
@@ -261,6 +369,7 @@ impl Z3LogParser for Z3Parser {
         let tidx = self.terms.new_term(term)?;
         let q = Quantifier {
             num_vars,
+            qid: quant_name.qid(),
             kind: quant_name,
             term: Some(tidx),
             vars: None,
@@ -300,17 +409,29 @@ impl Z3LogParser for Z3Parser {
         );
         let kind = TermKind::parse_proof_app(is_proof, name);
         // TODO: add rewrite, monotonicity cases
-        let child_ids = self.gobble_children(l)?;
+        let child_ids = if self.summary {
+            Default::default()
+        } else {
+            self.gobble_children(l)?
+        };
         let term = Term {
             id: Some(full_id),
             kind,
             child_ids,
         };
-        self.terms.new_term(term)?;
+        let tidx = self.terms.new_term(term)?;
+        // With proof logging the proof term of an instantiation may only be
+        // created after its `[instance]` line.
+        for iidx in self.pending_proofs.remove(&full_id).unwrap_or_default() {
+            self.insts[iidx].proof_id = Some(Either::Left(tidx));
+        }
         Ok(())
     }
 
     fn attach_meaning<'a>(&mut self, mut l: impl Iterator<Item = &'a str>) -> Result<()> {
+        if self.summary {
+            return Ok(());
+        }
         let id = l.next().ok_or(Error::UnexpectedNewline)?;
         let theory = IString(
             self.strings
@@ -318,8 +439,11 @@ impl Z3LogParser for Z3Parser {
         );
         let value = IString(self.strings.get_or_intern(l.collect::<Vec<_>>().join(" ")));
         let meaning = Meaning { theory, value };
-        let idx = self.terms.parse_existing_id(&mut self.strings, id)?;
-        self.terms.new_meaning(idx, meaning)?;
+        // The meaning may be attached before the term is defined.
+        match self.terms.parse_id(&mut self.strings, id)? {
+            Either::Left(idx) => self.terms.new_meaning(idx, meaning)?,
+            Either::Right(id) => self.terms.new_pending_meaning(id, meaning)?,
+        }
         Ok(())
     }
 
@@ -333,7 +457,11 @@ impl Z3LogParser for Z3Parser {
         Ok(())
     }
 
-    fn attach_enode<'a>(&mut self, mut l: impl Iterator<Item = &'a str>) -> Result<()> {
+    fn attach_enode<'a>(
+        &mut self,
+        mut l: impl Iterator<Item = &'a str>,
+        line_no: usize,
+    ) -> Result<()> {
         let id = l.next().ok_or(Error::UnexpectedNewline)?;
         let idx = self.terms.parse_existing_id(&mut self.strings, id);
         let Ok(idx) = idx else {
@@ -352,7 +480,7 @@ impl Z3LogParser for Z3Parser {
         let iidx = created_by.as_ref().map(|(i, _)| *i);
         let enode = self
             .egraph
-            .new_enode(iidx, idx, z3_generation, &self.stack)?;
+            .new_enode(iidx, idx, z3_generation, Some(line_no), &self.stack)?;
         if let Some((_, yields_terms)) = created_by {
             // If `None` then this is a ground term not created by an instantiation.
             yields_terms.try_reserve(1)?;
@@ -362,6 +490,9 @@ impl Z3LogParser for Z3Parser {
     }
 
     fn eq_expl<'a>(&mut self, mut l: impl Iterator<Item = &'a str>) -> Result<()> {
+        if self.skip_equalities {
+            return Ok(());
+        }
         let from = self.parse_existing_enode(l.next().ok_or(Error::UnexpectedNewline)?)?;
         let kind = l.next().ok_or(Error::UnexpectedNewline)?;
         let eq_expl = {
@@ -481,6 +612,16 @@ impl Z3LogParser for Z3Parser {
                 }
                 cond
             });
+            if self.skip_equalities {
+                for t in Self::gobble_tuples::<true>(l) {
+                    let (from, to) = t?;
+                    let from = self.parse_existing_enode(from)?;
+                    let to = self.parse_existing_enode(to)?;
+                    blamed.try_reserve(1)?;
+                    blamed.push(BlameKind::UnknownEquality { from, to });
+                }
+                continue;
+            }
             self.append_trans_equality_tuples(l, true, |eq| {
                 blamed.try_reserve(1)?;
                 blamed.push(BlameKind::Equality { eq });
@@ -563,7 +704,7 @@ impl Z3LogParser for Z3Parser {
         Ok(())
     }
 
-    fn instance<'a>(&mut self, mut l: impl Iterator<Item = &'a str>) -> Result<()> {
+    fn instance<'a>(&mut self, mut l: impl Iterator<Item = &'a str>, line_no: usize) -> Result<()> {
         let fingerprint = l.next().ok_or(Error::UnexpectedNewline)?;
         let fingerprint = Fingerprint::parse(fingerprint)?;
         let mut proof = Self::iter_until_eq(&mut l, ";");
@@ -585,6 +726,10 @@ impl Z3LogParser for Z3Parser {
             proof_id,
             z3_generation,
             yields_terms: Default::default(),
+            line_no,
+            decision_level: self.stack.height() as u32,
+            parent_instantiation: self.inst_stack.last().map(|(i, _)| *i),
+            nesting_depth: self.inst_stack.len() as u32,
         };
         // In version 4.12.2, I have on very rare occasions seen an `[instance]`
         // repeated twice with the same fingerprint (without an intermediate
@@ -592,6 +737,10 @@ impl Z3LogParser for Z3Parser {
         let iidx =
             self.insts
                 .new_inst(fingerprint, inst, self.version_info.is_version(4, 12, 2))?;
+        if let Some(Either::Right(id)) = proof_id {
+            self.pending_proofs.try_reserve(1)?;
+            self.pending_proofs.entry(id).or_default().push(iidx);
+        }
         self.inst_stack.try_reserve(1)?;
         self.inst_stack.push((iidx, Vec::new()));
         Ok(())
@@ -607,6 +756,34 @@ impl Z3LogParser for Z3Parser {
         self.terms.end_of_file();
     }
 
+    fn conflict<'a>(&mut self, mut l: impl Iterator<Item = &'a str>) -> Result<()> {
+        let mut lits = Vec::new();
+        while let Some(word) = l.next() {
+            let (id, negated) = match word {
+                "true" | "false" => continue,
+                "(not" => {
+                    let id = l.next().ok_or(Error::UnexpectedNewline)?;
+                    let id = id
+                        .strip_suffix(')')
+                        .ok_or_else(|| Error::InvalidConflictLit(id.to_string()))?;
+                    (id, true)
+                }
+                id => (id, false),
+            };
+            let term = self.terms.parse_existing_id(&mut self.strings, id)?;
+            let enode = self.egraph.get_enode(term, &self.stack).ok();
+            lits.try_reserve(1)?;
+            lits.push(ConflictLit {
+                term,
+                negated,
+                enode,
+            });
+        }
+        self.conflicts.try_reserve(1)?;
+        self.conflicts.push(BoxSlice(lits.into_boxed_slice()));
+        Ok(())
+    }
+
     fn push<'a>(&mut self, mut l: impl Iterator<Item = &'a str>) -> Result<()> {
         let scope = l.next().ok_or(Error::UnexpectedNewline)?;
         let scope = scope.parse::<usize>().map_err(Error::InvalidFrameInteger)?;
@@ -624,6 +801,11 @@ impl Z3LogParser for Z3Parser {
         Self::expect_completed(l)?;
         self.stack.pop_frames(num, scope)
     }
+
+    #[cfg(feature = "timing")]
+    fn timing_mut(&mut self) -> &mut ParseTiming {
+        &mut self.timing
+    }
 }
 
 impl Z3Parser {
@@ -631,6 +813,16 @@ impl Z3Parser {
         self.terms.meaning(tidx)
     }
 
+    /// The term which enode `e` was attached to, same as `parser[e].owner`.
+    pub fn enode_term(&self, e: ENodeIdx) -> TermIdx {
+        self[e].owner
+    }
+    /// The term of enode `e` together with its meaning, see
+    /// [`Z3Parser::enode_term`].
+    pub fn enode_term_and_meaning(&self, e: ENodeIdx) -> TermAndMeaning<'_> {
+        self.terms.get_term(self.enode_term(e))
+    }
+
     pub fn quant_count_incl_theory_solving(&self) -> (usize, bool) {
         (self.quantifiers.len(), self.insts.has_theory_solving_inst())
     }
@@ -641,6 +833,480 @@ impl Z3Parser {
     pub fn instantiations(&self) -> impl Iterator<Item = (InstIdx, &Instantiation)> {
         self.insts.insts.iter_enumerated()
     }
+    /// The key identifying instantiation `i` across traces, see [`InstKey`].
+    /// On a summary parser distinct instantiations may share a key, see
+    /// [`InstKey::bound_terms`].
+    pub fn inst_key(&self, i: InstIdx) -> InstKey {
+        let kind = &self[self[i].match_].kind;
+        let quant = match kind {
+            MatchKind::TheorySolving { axiom_id, .. } => self[axiom_id.namespace].to_string(),
+            kind => match &self[kind.quant_idx().unwrap()].kind {
+                QuantKind::Other(name) | QuantKind::NamedQuant(name) => self[*name].to_string(),
+                QuantKind::Lambda => "<lambda>".to_string(),
+                QuantKind::UnnamedQuant { name, id } => format!("{}!{id}", &self[*name]),
+            },
+        };
+        let bound_terms = kind.bound_terms(|e| self[e].owner, |t| t);
+        InstKey {
+            quant,
+            fingerprint: self[i].fingerprint,
+            bound_terms: bound_terms
+                .into_iter()
+                .map(|t| self.structural_hash(t))
+                .collect(),
+        }
+    }
+    /// The keys of all instantiations, e.g. to find the instantiations of
+    /// another trace which are not in this one.
+    pub fn inst_keys(&self) -> fxhash::FxHashSet<InstKey> {
+        self.instantiations()
+            .map(|(i, _)| self.inst_key(i))
+            .collect()
+    }
+    /// All matches, including those which were never instantiated.
+    pub fn matches(&self) -> &TiVec<MatchIdx, Match> {
+        &self.insts.matches
+    }
+    /// Each match paired with the first instantiation of it, or `None` if the
+    /// match was never instantiated (e.g. it was still pending at the end of
+    /// the log).
+    pub fn matches_with_instantiations(
+        &self,
+    ) -> impl Iterator<Item = (MatchIdx, &Match, Option<InstIdx>)> {
+        let mut inst_of = vec![None; self.insts.matches.len()];
+        for (inst, data) in self.instantiations() {
+            inst_of[usize::from(data.match_)].get_or_insert(inst);
+        }
+        self.insts
+            .matches
+            .iter_enumerated()
+            .zip(inst_of)
+            .map(|((idx, match_), inst)| (idx, match_, inst))
+    }
+    /// The clauses of the `[conflict]` lines of the log, in order.
+    pub fn conflicts(&self) -> &[BoxSlice<ConflictLit>] {
+        &self.conflicts
+    }
+    /// Builds the graph of congruence closure reasoning, separate from the
+    /// instantiation graph. Node `i` holds `ENodeIdx` `i` and each given
+    /// equality is an edge from its [`from`](EqualityExpl::from) to its
+    /// [`to`](EqualityExpl::to) enode.
+    pub fn equality_graph(&self) -> DiGraph<ENodeIdx, EqualityExpl> {
+        let given = &self.egraph.equalities.given;
+        let mut graph = DiGraph::with_capacity(self.egraph.enodes.len(), given.len());
+        for enode in self.egraph.enodes.keys() {
+            graph.add_node(enode);
+        }
+        for expl in given.iter() {
+            if matches!(expl, EqualityExpl::Root { .. }) {
+                continue;
+            }
+            let from = NodeIndex::new(expl.from().into());
+            let to = NodeIndex::new(expl.to().into());
+            graph.add_edge(from, to, expl.clone());
+        }
+        graph
+    }
+    /// How many given equalities there are of each kind. A log dominated by
+    /// congruence equalities points at a lot of work in the e-graph, one
+    /// dominated by theory equalities at the combination of theories.
+    pub fn equality_kind_counts(&self) -> EqKindCounts {
+        let mut counts = EqKindCounts::default();
+        for expl in self.egraph.equalities.given.iter() {
+            let count = match expl {
+                EqualityExpl::Root { .. } => &mut counts.root,
+                EqualityExpl::Literal { .. } => &mut counts.literal,
+                EqualityExpl::Congruence { .. } => &mut counts.congruence,
+                EqualityExpl::Theory { .. } => &mut counts.theory,
+                EqualityExpl::Axiom { .. } => &mut counts.axiom,
+                EqualityExpl::Unknown { .. } => &mut counts.unknown,
+            };
+            *count += 1;
+        }
+        counts
+    }
+
+    /// Hashes the shape of term `t`: the kind and name of each subterm in the
+    /// tree, ignoring term ids. Structurally identical terms hash to the same
+    /// value, even if they were created separately. Shared subterms are only
+    /// hashed once per call. `None` on a [`Z3Parser::new_summary`] parser.
+    pub fn structural_hash(&self, t: TermIdx) -> Option<u64> {
+        if self.summary {
+            return None;
+        }
+        let mut memo = FxHashMap::<TermIdx, u64>::default();
+        // Explicit stack rather than recursion since terms can be very deep.
+        let mut stack = vec![t];
+        while let Some(&next) = stack.last() {
+            if memo.contains_key(&next) {
+                stack.pop();
+                continue;
+            }
+            let term = &self[next];
+            let missing = stack.len();
+            stack.extend(term.child_ids.iter().filter(|c| !memo.contains_key(c)));
+            if stack.len() != missing {
+                continue;
+            }
+            stack.pop();
+            let mut hasher = fxhash::FxHasher::default();
+            match term.kind {
+                TermKind::Var(idx) => (0u8, idx).hash(&mut hasher),
+                TermKind::ProofOrApp(ProofOrApp { is_proof, name }) => {
+                    (1u8, is_proof, &self[name]).hash(&mut hasher)
+                }
+                TermKind::Quant(q) => (2u8, self[q].num_vars).hash(&mut hasher),
+                TermKind::Generalised => 3u8.hash(&mut hasher),
+            }
+            for child in term.child_ids.iter() {
+                memo[child].hash(&mut hasher);
+            }
+            memo.insert(next, hasher.finish());
+        }
+        Some(memo[&t])
+    }
+
+    /// The subterm of `t` reached by following `path`, where each element is
+    /// the index of the child to descend into. An empty path returns `t`
+    /// itself, an out of range index returns `None`. Always `None` on a
+    /// [`Z3Parser::new_summary`] parser since term arguments are not kept.
+    pub fn subterm_at(&self, t: TermIdx, path: &[usize]) -> Option<TermIdx> {
+        if self.summary {
+            return None;
+        }
+        path.iter()
+            .try_fold(t, |t, &child| self[t].child_ids.get(child).copied())
+    }
+
+    /// Displays the subterm of `t` at `path`, see [`Z3Parser::subterm_at`].
+    /// Bound variables of quantifiers above the subterm are shown by their
+    /// index since the quantifier is not part of what is displayed.
+    #[cfg(feature = "display")]
+    pub fn subterm_display(
+        &self,
+        t: TermIdx,
+        path: &[usize],
+        config: DisplayConfiguration,
+    ) -> Option<String> {
+        let subterm = self.subterm_at(t, path)?;
+        let term_display = TermDisplayContext::basic();
+        let ctxt = DisplayCtxt {
+            parser: self,
+            term_display: &term_display,
+            config,
+        };
+        Some(subterm.with(&ctxt).to_string())
+    }
+
+    /// Renders the DAG of subterms of `t` in the DOT format. Each distinct
+    /// subterm is a single node, so shared subterms have several incoming
+    /// edges. Applications are labelled by their operator and quantifiers by
+    /// their name, with edges to the children in argument order. Leaves are
+    /// displayed in full, showing variable names and meanings. Use a
+    /// `config` with `html` disabled. `None` on a [`Z3Parser::new_summary`]
+    /// parser since term arguments are not kept.
+    #[cfg(feature = "display")]
+    pub fn term_dot(&self, t: TermIdx, config: DisplayConfiguration) -> Option<String> {
+        use std::fmt::Write;
+
+        if self.summary {
+            return None;
+        }
+
+        let term_display = TermDisplayContext::basic();
+        let ctxt = DisplayCtxt {
+            parser: self,
+            term_display: &term_display,
+            config,
+        };
+        let mut dot = String::from("digraph {\n    ordering=out;\n");
+        let mut seen = fxhash::FxHashSet::default();
+        let mut stack = vec![t];
+        while let Some(next) = stack.pop() {
+            if !seen.insert(next) {
+                continue;
+            }
+            let term = &self[next];
+            let label = match term.kind {
+                _ if term.child_ids.is_empty() => next.with(&ctxt).to_string(),
+                TermKind::ProofOrApp(app) => self[app.name].to_string(),
+                TermKind::Quant(q) => self[q]
+                    .preferred_kind(ctxt.config.prefer_qid)
+                    .with(&ctxt)
+                    .to_string(),
+                TermKind::Var(_) | TermKind::Generalised => next.with(&ctxt).to_string(),
+            };
+            let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+            let id = usize::from(next);
+            writeln!(dot, "    n{id} [label=\"{label}\"];").unwrap();
+            for &child in term.child_ids.iter() {
+                writeln!(dot, "    n{id} -> n{};", usize::from(child)).unwrap();
+            }
+            stack.extend(term.child_ids.iter().rev());
+        }
+        dot.push('}');
+        Some(dot)
+    }
+
+    /// A label for quantifier `q` which is more readable than the `name!id`
+    /// Z3 assigns to quantifiers without a name in the SMT file. Quantifiers
+    /// with a name (and theory solving) are labelled as usual. Unnamed ones
+    /// are labelled `∀ over f, g` by the function symbols at the head of the
+    /// terms of their first trigger, falling back to the usual label if they
+    /// have no trigger. A [`Z3Parser::new_summary`] parser does not keep the
+    /// terms of triggers, so there all quantifiers get the usual label.
+    #[cfg(feature = "display")]
+    pub fn derived_quant_label(&self, q: QuantIdx, config: DisplayConfiguration) -> String {
+        let quant = &self[q];
+        let trigger = quant
+            .term
+            .filter(|_| !self.summary && quant.kind.user_name().is_none())
+            .and_then(|t| {
+                let children = &self[t].child_ids;
+                // All children except the body are patterns.
+                children
+                    .len()
+                    .checked_sub(1)
+                    .and_then(|n| children[..n].first().copied())
+            });
+        let heads: Vec<_> = trigger
+            .iter()
+            .flat_map(|&p| self[p].child_ids.iter())
+            .filter_map(|&t| self[t].kind.app_name())
+            .map(|name| &self[name])
+            .collect();
+        if !heads.is_empty() {
+            return format!("∀ over {}", heads.join(", "));
+        }
+        let term_display = TermDisplayContext::basic();
+        let ctxt = DisplayCtxt {
+            parser: self,
+            term_display: &term_display,
+            config,
+        };
+        quant
+            .preferred_kind(ctxt.config.prefer_qid)
+            .with(&ctxt)
+            .to_string()
+    }
+
+    /// The name of quantifier `q` as it appears in the log (e.g. `foo!12`,
+    /// `<null>` for lambdas) after applying `normalizer`. Quantifiers whose
+    /// canonical names are equal are treated as the same when searching and
+    /// grouping.
+    #[cfg(feature = "display")]
+    pub fn canonical_quant_name(&self, q: QuantIdx, normalizer: NameNormalizer) -> String {
+        match self[q].kind {
+            QuantKind::Other(name) | QuantKind::NamedQuant(name) => {
+                normalizer.normalize(&self[name])
+            }
+            QuantKind::Lambda => normalizer.normalize("<null>"),
+            QuantKind::UnnamedQuant { name, id } => {
+                normalizer.normalize(&format!("{}!{id}", &self[name]))
+            }
+        }
+    }
+
+    /// All quantifiers grouped by their [`Z3Parser::canonical_quant_name`],
+    /// ordered by name. Each group lists its quantifiers in the order they
+    /// were created.
+    #[cfg(feature = "display")]
+    pub fn quants_by_canonical_name(
+        &self,
+        normalizer: NameNormalizer,
+    ) -> Vec<(String, Vec<QuantIdx>)> {
+        let mut groups = std::collections::BTreeMap::<String, Vec<QuantIdx>>::new();
+        for (q, _) in self.quantifiers() {
+            let name = self.canonical_quant_name(q, normalizer);
+            groups.entry(name).or_default().push(q);
+        }
+        groups.into_iter().collect()
+    }
+
+    /// How long the parser spent in each phase so far.
+    #[cfg(feature = "timing")]
+    pub fn timing(&self) -> ParseTiming {
+        self.timing
+    }
+
+    /// The position of instantiation `i` in the order Z3 fired them, starting
+    /// from `0`. Instantiations are numbered in the order of their
+    /// `[instance]` lines, so this is the index of `i`: unlike line numbers
+    /// it is unambiguous and has no gaps.
+    pub fn firing_order(&self, i: InstIdx) -> u32 {
+        usize::from(i) as u32
+    }
+
+    /// The first and last line of the log on which each quantifier was
+    /// instantiated, `None` for quantifiers which never were.
+    pub fn quantifier_activity(&self) -> TiVec<QuantIdx, Option<(usize, usize)>> {
+        let mut activity: TiVec<QuantIdx, Option<(usize, usize)>> =
+            self.quantifiers.iter().map(|_| None).collect();
+        for (_, inst) in self.instantiations() {
+            let Some(quant) = self[inst.match_].kind.quant_idx() else {
+                continue;
+            };
+            // Instantiations are in the order they appear in the log.
+            activity[quant]
+                .get_or_insert((inst.line_no, inst.line_no))
+                .1 = inst.line_no;
+        }
+        activity
+    }
+
+    /// How many of the terms yielded by instantiation `i` were matched by the
+    /// trigger of some other instantiation, and how many terms it yielded in
+    /// total. A low ratio means that `i` mostly produced dead terms. Terms
+    /// only used through equalities are not counted as used.
+    pub fn yield_usage(&self, i: InstIdx) -> (usize, usize) {
+        let yields = &self[i].yields_terms;
+        let used = yields
+            .iter()
+            .filter(|&&e| self.insts.blamed_by(e).iter().any(|&j| j != i))
+            .count();
+        (used, yields.len())
+    }
+
+    /// The terms of all enodes which were not created by an instantiation,
+    /// ordered by term. These are the ground terms of the input, as opposed
+    /// to those produced while solving. Terms which never became an enode,
+    /// e.g. quantifier bodies, are not included.
+    pub fn initial_terms(&self) -> Vec<TermIdx> {
+        let mut terms: Vec<_> = self
+            .egraph
+            .enodes
+            .iter()
+            .filter(|enode| enode.created_by.is_none())
+            .map(|enode| enode.owner)
+            .collect();
+        terms.sort_unstable();
+        terms.dedup();
+        terms
+    }
+
+    /// The id as it appears in the log, e.g. `arith#` or `#123`.
+    pub fn term_id_name(&self, id: TermId) -> String {
+        let namespace = &self[id.namespace];
+        let id = id.id.map(|id| id.to_string()).unwrap_or_default();
+        format!("{namespace}#{id}")
+    }
+
+    /// Counts of the terms created while parsing and of the function symbols
+    /// they apply (proof steps are not counted as symbols). `None` on a
+    /// [`Z3Parser::new_summary`] parser since term arguments are not kept.
+    pub fn term_stats(&self) -> Option<TermStats> {
+        if self.summary {
+            return None;
+        }
+        let mut stats = TermStats::default();
+        let mut symbols = FxHashMap::<IString, usize>::default();
+        for term in self.terms.parsed_terms() {
+            stats.distinct_terms += 1;
+            stats.total_children += term.child_ids.len();
+            if let TermKind::ProofOrApp(app) = term.kind {
+                if !app.is_proof {
+                    *symbols.entry(app.name).or_default() += 1;
+                }
+            }
+        }
+        stats.top_symbols = symbols
+            .iter()
+            .map(|(&name, &count)| (name, count))
+            .collect();
+        stats
+            .top_symbols
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| self[a.0].cmp(&self[b.0])));
+        Some(stats)
+    }
+
+    /// The term of the trigger of instantiation `i` which was matched by the
+    /// blamed enode `e`. `None` if `e` is not blamed by `i` or if `i` has no
+    /// trigger (e.g. MBQI or theory solving). Triggers are blamed in the
+    /// order of their terms in the `pattern`. Always `None` on a
+    /// [`Z3Parser::new_summary`] parser, which does not keep the terms of
+    /// patterns.
+    pub fn blame_trigger_term(&self, i: InstIdx, e: ENodeIdx) -> Option<TermIdx> {
+        if self.summary {
+            return None;
+        }
+        let match_ = &self[self[i].match_];
+        let pattern = match_.kind.pattern()?;
+        let position = match_.trigger_matches().position(|b| b.enode() == e)?;
+        self[pattern].child_ids.get(position).copied()
+    }
+
+    /// The subterms of the resulting term of instantiation `i` which are the
+    /// terms of its trigger with the bound terms substituted, i.e. where the
+    /// trigger shows up in the instantiated formula. Pass them as
+    /// [`DisplayConfiguration::highlight_terms`] to highlight them. Empty if
+    /// `i` has no trigger or no resulting term. `None` on a
+    /// [`Z3Parser::new_summary`] parser since term arguments are not kept.
+    pub fn instantiated_triggers(&self, i: InstIdx) -> Option<Vec<TermIdx>> {
+        if self.summary {
+            return None;
+        }
+        let inst = &self[i];
+        let kind = &self[inst.match_].kind;
+        let (Some(pattern), Some(resulting_term)) = (kind.pattern(), inst.get_resulting_term())
+        else {
+            return Some(Vec::new());
+        };
+        let bound = kind.bound_terms(|e| self[e].owner, |t| t);
+        let triggers = &self[pattern].child_ids;
+        let mut found = Vec::new();
+        let mut seen = fxhash::FxHashSet::default();
+        // Explicit stack rather than recursion since terms can be very deep.
+        let mut stack = vec![resulting_term];
+        while let Some(t) = stack.pop() {
+            if !seen.insert(t) {
+                continue;
+            }
+            if triggers
+                .iter()
+                .any(|&trigger| self.is_trigger_instance(t, trigger, &bound))
+            {
+                found.push(t);
+            }
+            stack.extend(self[t].child_ids.iter().copied());
+        }
+        found.sort_unstable();
+        Some(found)
+    }
+
+    /// Whether `t` is `trigger` with each variable replaced by its term in
+    /// `bound`.
+    fn is_trigger_instance(&self, t: TermIdx, trigger: TermIdx, bound: &[TermIdx]) -> bool {
+        let (term, trigger) = (&self[t], &self[trigger]);
+        match trigger.kind {
+            TermKind::Var(idx) => bound.get(idx) == Some(&t),
+            kind => {
+                term.kind == kind
+                    && term.child_ids.len() == trigger.child_ids.len()
+                    && term
+                        .child_ids
+                        .iter()
+                        .zip(trigger.child_ids.iter())
+                        .all(|(&t, &trigger)| self.is_trigger_instance(t, trigger, bound))
+            }
+        }
+    }
+
+    /// All instantiations which matched `e` as one of the terms of their
+    /// trigger, in the order they were instantiated.
+    pub fn instantiations_blaming(&self, e: ENodeIdx) -> Vec<InstIdx> {
+        self.insts.blamed_by(e).to_vec()
+    }
+
+    /// Every string interned so far, in the order in which they were first
+    /// interned. Useful when debugging the parser, e.g. to spot the same
+    /// symbol interned twice with different whitespace. Usage counts are not
+    /// tracked since strings are interned from many places during parsing.
+    pub fn dump_strings(&self) -> Vec<(IString, &str)> {
+        self.strings
+            .iter()
+            .map(|(spur, s)| (IString(spur), s))
+            .collect()
+    }
 }
 
 impl std::ops::Index<TermIdx> for Z3Parser {
@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+
+use smt_log_parser::{LogParser, Z3Parser};
+
+pub fn parse(log: &str) -> Z3Parser {
+    Z3Parser::from_str(log).process_all().unwrap()
+}
+
+/// The terms `a` (`#1`) and `f(a)` (`#2`) with enodes, and the trigger
+/// `{f(x)}` (`#5`) on the bound variable `x` (`#3`). Quantifiers using it are
+/// declared with `[mk-quant] #n name 1 #5 body`.
+pub fn f_pattern_log(rest: &str) -> String {
+    "[mk-app] #1 a\n[mk-app] #2 f #1\n[mk-var] #3 0\n[mk-app] #4 f #3\n\
+        [mk-app] #5 pattern #4\n[attach-enode] #1 0\n[attach-enode] #2 0\n"
+        .to_string()
+        + rest
+        + "[eof]\n"
+}
+
+/// The quantifier `q` (`#6`) with the body `g(x)` (`#7`), matched on `f(a)`
+/// and instantiated once with `x ↦ a`.
+pub const ONE_INSTANCE: &str = "[mk-app] #7 g #3\n[mk-quant] #6 q 1 #5 #7\n\
+    [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n[end-of-instance]\n";
@@ -0,0 +1,112 @@
+mod common;
+
+use common::{f_pattern_log, parse, ONE_INSTANCE};
+use smt_log_parser::{
+    analysis::{analysis::cost::DefaultCost, raw::NodeKind, InstGraph},
+    items::{EqGivenIdx, InstIdx},
+};
+
+#[test]
+fn cost_rankings_agree() {
+    // Instantiation 0 yields `f(f(a))` which instantiation 1 matches, so it
+    // is the most expensive. Instantiations 1 and 2 tie and keep their order.
+    let parser = parse(&f_pattern_log(
+        "[mk-app] #7 g #3\n[mk-quant] #6 q 1 #5 #7\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n\
+        [mk-app] #8 f #2\n[attach-enode] #8 1\n[end-of-instance]\n\
+        [new-match] 0x2 #6 #5 #2 ; #8\n[instance] 0x2 ; 2\n[end-of-instance]\n\
+        [new-match] 0x3 #6 #5 #1 ; #2\n[instance] 0x3 ; 1\n[end-of-instance]\n",
+    ));
+    let top = parser.top_instantiations(3);
+    assert_eq!(top, [0, 1, 2].map(InstIdx::from));
+
+    let mut graph = InstGraph::new(&parser).unwrap();
+    let costs = parser.instantiation_costs();
+    assert_eq!(graph.instantiation_costs().raw, costs.raw);
+    graph.recompute_costs(DefaultCost, &parser);
+    assert_eq!(graph.instantiation_costs().raw, costs.raw);
+    let ranked: Vec<_> = graph
+        .analysis
+        .cost
+        .iter()
+        .filter_map(|&n| match *graph.raw[n].kind() {
+            NodeKind::Instantiation(i) => Some(i),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(ranked, top);
+}
+
+#[test]
+fn set_visible_set() {
+    let parser = parse(&f_pattern_log(ONE_INSTANCE));
+    let mut graph = InstGraph::new(&parser).unwrap();
+    let inst = graph.raw.index(NodeKind::Instantiation(InstIdx::from(0)));
+    graph.raw.set_visible_set(&[inst].into_iter().collect());
+    assert_eq!(graph.raw.visible_nodes(), 1);
+    assert!(graph.raw[inst].visible());
+    graph.raw.set_visible_set(&Default::default());
+    assert_eq!(graph.raw.visible_nodes(), 0);
+}
+
+#[test]
+fn invalid_cost_percentile() {
+    let parser = parse(&f_pattern_log(ONE_INSTANCE));
+    let mut graph = InstGraph::new(&parser).unwrap();
+    graph.raw.reset_visibility_to(false);
+    let visible = graph.raw.visible_nodes();
+    assert!(graph.keep_cost_percentile(0.9, 0.5).is_err());
+    assert!(graph.keep_cost_percentile(0.0, 1.5).is_err());
+    assert_eq!(graph.raw.visible_nodes(), visible);
+    assert!(graph.keep_cost_percentile(0.0, 1.0).is_ok());
+    assert_eq!(graph.raw.visible_nodes(), visible);
+}
+
+#[test]
+fn dimmed_nodes_stay_visible() {
+    let parser = parse(&f_pattern_log(ONE_INSTANCE));
+    let mut graph = InstGraph::new(&parser).unwrap();
+    graph.raw.reset_visibility_to(false);
+    let inst = graph.raw.index(NodeKind::Instantiation(InstIdx::from(0)));
+    let before = graph.to_visible();
+    graph.raw.set_dimmed([inst].into_iter().collect());
+    assert!(!graph.visible_unchanged(&before));
+    let after = graph.to_visible();
+    assert_eq!(after.graph.node_count(), before.graph.node_count());
+    assert!(after
+        .graph
+        .node_weights()
+        .all(|n| n.dimmed == (n.idx == inst)));
+}
+
+#[test]
+fn inline_equalities() {
+    // Instantiation 0 yields `b = a` which instantiation 1 relies on to match
+    // `f(a)` as `f(b)`.
+    let parser = parse(&f_pattern_log(
+        "[mk-app] #7 g #3\n[mk-quant] #6 q 1 #5 #7\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n\
+        [mk-app] #8 b\n[mk-app] #9 = #8 #1\n[attach-enode] #8 1\n[attach-enode] #9 1\n\
+        [end-of-instance]\n[eq-expl] #8 lit #9 ; #1\n\
+        [new-match] 0x2 #6 #5 #8 ; #2 (#1 #8)\n[instance] 0x2 ; 2\n[end-of-instance]\n",
+    ));
+    let mut graph = InstGraph::new(&parser).unwrap();
+    graph.raw.reset_visibility_to(false);
+
+    let inline = graph.to_visible_inline_equalities();
+    assert!(inline.inline_equalities());
+    assert!(inline.graph.node_weights().all(|n| !matches!(
+        graph.raw[n.idx].kind(),
+        NodeKind::GivenEquality(..) | NodeKind::TransEquality(_)
+    )));
+    let eqs: Vec<_> = inline
+        .graph
+        .edge_weights()
+        .filter_map(|e| e.kind(&graph).given_equality())
+        .collect();
+    assert_eq!(eqs, [(EqGivenIdx::from(0), None)]);
+
+    // The equality nodes are still visible in the raw graph.
+    let visible = graph.to_visible();
+    assert_eq!(visible.graph.node_count(), graph.raw.graph.node_count());
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{f_pattern_log, parse};
+use smt_log_parser::{
+    items::{InstIdx, TermIdx},
+    parsers::StreamParser,
+    Z3Parser,
+};
+
+#[test]
+fn meaning_before_term() {
+    let log = "[attach-meaning] #1 bv #b01\n[mk-app] #1 bv\n[attach-meaning] #1 bv #b10\n[eof]\n";
+    let parser = parse(log);
+    let meaning = parser.meaning(TermIdx::from(0)).unwrap();
+    assert_eq!(&parser[meaning.value], "#b10");
+}
+
+#[test]
+fn summary_drops_term_bodies() {
+    let log = "[mk-app] #1 a\n[mk-app] #2 f #1 #1\n[attach-meaning] #1 arith 1\n[eof]\n";
+    let parser = StreamParser::with_parser(log.as_bytes(), Z3Parser::new_summary())
+        .process_all()
+        .unwrap();
+    assert!(parser.is_summary());
+    assert!(parser[TermIdx::from(1)].child_ids.is_empty());
+    assert!(parser.meaning(TermIdx::from(0)).is_none());
+    assert!(parser.term_stats().is_none());
+    assert!(parser.subterm_at(TermIdx::from(1), &[0]).is_none());
+    assert!(parser.insts_containing_symbol("a").is_none());
+    let pattern = "(f ?x ?x)".parse().unwrap();
+    assert!(parser.find_terms_matching(&pattern).is_none());
+}
+
+#[test]
+fn multi_line_quoted_symbols() {
+    // The second line of the symbol starts with `[`, like a new entry would.
+    let log = "[mk-app] #1 |a\n[b c|\n[mk-app] #2 f #1 #1\n[eof]\n";
+    let parser = parse(log);
+    assert_eq!(
+        parser.term_sexpr(TermIdx::from(1)),
+        "(f |a\n[b c| |a\n[b c|)"
+    );
+
+    let log = "[mk-app] #1 x\n[attach-meaning] #1 seq \"a (\n  b\"\n[eof]\n";
+    let parser = parse(log);
+    let meaning = parser.meaning(TermIdx::from(0)).unwrap();
+    assert_eq!(&parser[meaning.value], "\"a (\n  b\"");
+}
+
+#[test]
+fn proof_defined_after_instance() {
+    let log = f_pattern_log(
+        "[mk-app] #7 g #3\n[mk-quant] #6 q 1 #5 #7\n[new-match] 0x1 #6 #5 #1 ; #2\n\
+            [instance] 0x1 #8 ; 1\n[end-of-instance]\n[mk-proof] #8 quant-inst #7\n",
+    );
+    let parser = parse(&log);
+    let proof = parser[InstIdx::from(0)].get_resulting_term().unwrap();
+    assert_eq!(proof, TermIdx::from(7));
+}
+
+#[test]
+fn shared_string_table() {
+    let first = parse("[mk-app] #1 a\n[mk-app] #2 f #1\n[eof]\n");
+    let second = Z3Parser::with_strings(first.strings.clone());
+    let second = StreamParser::with_parser(
+        "[mk-app] #1 g\n[mk-app] #2 f #1\n[eof]\n".as_bytes(),
+        second,
+    )
+    .process_all()
+    .unwrap();
+    let name = |parser: &Z3Parser, t: usize| parser[TermIdx::from(t)].kind.app_name().unwrap();
+    // The first parser can still resolve its strings.
+    assert_eq!(&first[name(&first, 0)], "a");
+    assert_eq!(name(&first, 1), name(&second, 1));
+    assert_eq!(&second[name(&second, 0)], "g");
+}
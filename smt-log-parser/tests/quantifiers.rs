@@ -0,0 +1,193 @@
+mod common;
+
+use common::{f_pattern_log, parse};
+use smt_log_parser::{
+    display_with::NameNormalizer,
+    items::{ENodeIdx, InstIdx, MatchIdx, QuantIdx, TermIdx},
+};
+
+#[test]
+fn instantiated_triggers() {
+    // The body `g(f(x))` instantiated with `x ↦ a` contains the trigger
+    // `f(x)` as `f(a)`, but `g(f(a))` itself is no instance of it.
+    let parser = parse(&f_pattern_log(
+        "[mk-app] #7 g #4\n[mk-quant] #6 q 1 #5 #7\n\
+        [mk-app] #8 g #2\n[mk-app] #9 not #6\n[mk-app] #10 or #9 #8\n\
+        [mk-proof] #11 quant-inst #10\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 #11 ; 1\n[end-of-instance]\n",
+    ));
+    let triggers = parser.instantiated_triggers(InstIdx::from(0)).unwrap();
+    assert_eq!(triggers, [TermIdx::from(1)]);
+}
+
+#[test]
+fn canonical_quant_names() {
+    let parser = parse(&f_pattern_log(
+        "[mk-quant] #6 Foo!1 1 #5 #4\n[mk-quant] #7 foo!2 1 #5 #4\n[mk-quant] #8 bar 1 #5 #4\n",
+    ));
+    let groups = |lowercase, strip_id| {
+        let normalizer = NameNormalizer {
+            lowercase,
+            strip_id,
+        };
+        let groups = parser.quants_by_canonical_name(normalizer);
+        groups
+            .into_iter()
+            .map(|(name, qs)| (name, qs.len()))
+            .collect::<Vec<_>>()
+    };
+    let names = |names: &[(&str, usize)]| {
+        names
+            .iter()
+            .map(|&(n, c)| (n.to_string(), c))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        groups(false, false),
+        names(&[("Foo!1", 1), ("bar", 1), ("foo!2", 1)])
+    );
+    assert_eq!(
+        groups(false, true),
+        names(&[("Foo", 1), ("bar", 1), ("foo", 1)])
+    );
+    assert_eq!(groups(true, true), names(&[("bar", 1), ("foo", 2)]));
+}
+
+#[test]
+fn mbqi_only_quantifiers() {
+    // `q` is instantiated both by e-matching and MBQI, `r` only by MBQI and
+    // `s` never.
+    let parser = parse(&f_pattern_log(
+        "[mk-quant] #6 q 1 #5 #4\n[mk-quant] #7 r 1 #5 #4\n[mk-quant] #8 s 1 #5 #4\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n[end-of-instance]\n\
+        [inst-discovered] MBQI 0x2 #6 #1\n[instance] 0x2 ; 1\n[end-of-instance]\n\
+        [inst-discovered] MBQI 0x3 #7 #1\n[instance] 0x3 ; 1\n[end-of-instance]\n",
+    ));
+    assert_eq!(parser.mbqi_only_quantifiers(), [QuantIdx::from(1)]);
+}
+
+#[test]
+fn matches_with_instantiations() {
+    // The match `0x2` is never instantiated.
+    let parser = parse(&f_pattern_log(
+        "[mk-quant] #6 q 1 #5 #4\n[new-match] 0x1 #6 #5 #1 ; #2\n\
+        [new-match] 0x2 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n[end-of-instance]\n",
+    ));
+    let matches: Vec<_> = parser
+        .matches_with_instantiations()
+        .map(|(m, _, i)| (m, i))
+        .collect();
+    assert_eq!(
+        matches,
+        [
+            (MatchIdx::from(0), Some(InstIdx::from(0))),
+            (MatchIdx::from(1), None)
+        ]
+    );
+}
+
+#[test]
+fn ematching_pressure() {
+    // `q` is matched twice but instantiated once, `r` matched and
+    // instantiated once.
+    let parser = parse(&f_pattern_log(
+        "[mk-quant] #6 q 1 #5 #4\n[mk-quant] #7 r 1 #5 #4\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[new-match] 0x2 #6 #5 #1 ; #2\n\
+        [new-match] 0x3 #7 #5 #1 ; #2\n[instance] 0x1 ; 1\n[end-of-instance]\n\
+        [instance] 0x3 ; 1\n[end-of-instance]\n",
+    ));
+    let (q, r) = (QuantIdx::from(0), QuantIdx::from(1));
+    assert_eq!(parser.ematching_pressure(), [(q, 2, 1), (r, 1, 1)]);
+}
+
+#[test]
+fn qid_only_for_named_quantifiers() {
+    let parser = parse(&f_pattern_log(
+        "[mk-quant] #6 foo 1 #5 #4\n[mk-quant] #7 k!3 1 #5 #4\n[mk-lambda] #8 <null> 1 #5 #4\n",
+    ));
+    let qid = |q: usize| parser[QuantIdx::from(q)].qid.map(|qid| &parser[qid]);
+    assert_eq!(qid(0), Some("foo"));
+    assert_eq!(qid(1), None);
+    assert_eq!(qid(2), None);
+}
+
+#[test]
+fn blame_trigger_term() {
+    // The multi-pattern `{f(x), g(x)}` matched on `f(a)` and `g(a)`.
+    let parser = parse(
+        "[mk-app] #1 a\n[mk-app] #2 f #1\n[mk-app] #3 g #1\n[mk-var] #4 0\n\
+        [mk-app] #5 f #4\n[mk-app] #6 g #4\n[mk-app] #7 pattern #5 #6\n\
+        [mk-quant] #8 q 1 #7 #5\n[attach-enode] #1 0\n[attach-enode] #2 0\n\
+        [attach-enode] #3 0\n[new-match] 0x1 #8 #7 #1 ; #2 #3\n\
+        [instance] 0x1 ; 1\n[end-of-instance]\n[eof]\n",
+    );
+    let trigger = |e: usize| parser.blame_trigger_term(InstIdx::from(0), ENodeIdx::from(e));
+    assert_eq!(trigger(1), Some(TermIdx::from(4)));
+    assert_eq!(trigger(2), Some(TermIdx::from(5)));
+    assert_eq!(trigger(0), None);
+}
+
+#[test]
+fn inst_keys_across_traces() {
+    use smt_log_parser::analysis::InstGraph;
+    // Theory solving instantiations all have a zero fingerprint, so they are
+    // told apart by their bound terms.
+    let theory = |names: &[&str]| {
+        let log: String = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let id = i + 1;
+                format!(
+                    "[mk-app] #{id} {name}\n[inst-discovered] theory-solving 0x0 arith# #{id} ;\n\
+                    [instance] 0x0 ; 1\n[end-of-instance]\n"
+                )
+            })
+            .collect();
+        parse(&(log + "[eof]\n"))
+    };
+    let baseline = theory(&["a", "b"]).inst_keys();
+    assert_eq!(baseline.len(), 2);
+    let current = theory(&["b", "c", "a"]);
+    let mut graph = InstGraph::new(&current).unwrap();
+    assert_eq!(graph.show_only_new_vs(&current, &baseline), 1);
+    let visible: Vec<_> = graph
+        .raw
+        .graph
+        .node_weights()
+        .filter(|n| n.visible())
+        .filter_map(|n| n.kind().inst())
+        .collect();
+    assert_eq!(visible, [InstIdx::from(1)]);
+}
+
+#[test]
+fn yield_usage() {
+    // The first instantiation yields `b` and `f(b)`, only `f(b)` is matched
+    // by the second one.
+    let parser = parse(&f_pattern_log(
+        "[mk-app] #7 g #3\n[mk-quant] #6 q 1 #5 #7\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n\
+        [mk-app] #8 b\n[mk-app] #9 f #8\n[attach-enode] #8 1\n[attach-enode] #9 1\n\
+        [end-of-instance]\n[new-match] 0x2 #6 #5 #8 ; #9\n[instance] 0x2 ; 2\n[end-of-instance]\n",
+    ));
+    assert_eq!(parser.yield_usage(InstIdx::from(0)), (1, 2));
+    assert_eq!(parser.yield_usage(InstIdx::from(1)), (0, 0));
+}
+
+#[test]
+fn semantically_duplicate_instantiations() {
+    // `q` is instantiated with `x ↦ a` and `x ↦ b` where `b = a`.
+    let parser = parse(&f_pattern_log(
+        "[mk-app] #7 g #3\n[mk-quant] #6 q 1 #5 #7\n\
+        [new-match] 0x1 #6 #5 #1 ; #2\n[instance] 0x1 ; 1\n[end-of-instance]\n\
+        [mk-app] #8 b\n[mk-app] #9 f #8\n[attach-enode] #8 0\n[attach-enode] #9 0\n\
+        [mk-app] #10 = #8 #1\n[attach-enode] #10 0\n[eq-expl] #8 lit #10 ; #1\n\
+        [new-match] 0x2 #6 #5 #8 ; #9\n[instance] 0x2 ; 1\n[end-of-instance]\n",
+    ));
+    assert!(parser.duplicate_instantiations().is_empty());
+    assert_eq!(
+        parser.semantically_duplicate_instantiations(),
+        [[InstIdx::from(0), InstIdx::from(1)]]
+    );
+}
@@ -0,0 +1,20 @@
+use smt_log_parser::{items::TermId, Error, LogParser, StringTable, Z3Parser};
+
+#[test]
+fn oversized_term_ids() {
+    let mut strings = StringTable::with_hasher(fxhash::FxBuildHasher::default());
+    let largest = TermId::parse(&mut strings, "#4294967294").unwrap();
+    assert_eq!(largest.order(), u32::MAX);
+    assert!(matches!(
+        TermId::parse(&mut strings, "#4294967295"),
+        Err(Error::IdNumberOverflow(_))
+    ));
+    assert!(matches!(
+        TermId::parse(&mut strings, "name#99999999999"),
+        Err(Error::InvalidIdNumber(_))
+    ));
+
+    // The parser should skip over the bad lines rather than panicking.
+    let log = "[mk-app] #4294967295 f\n[mk-app] #1 g\n[eof]\n";
+    assert!(Z3Parser::from_str(log).process_all().is_ok());
+}
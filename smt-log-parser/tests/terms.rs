@@ -0,0 +1,63 @@
+mod common;
+
+use common::parse;
+use smt_log_parser::items::TermIdx;
+
+#[test]
+fn sexpr_quotes_symbols() {
+    use smt_log_parser::parsers::z3::sexpr::quote_symbol;
+    assert_eq!(quote_symbol("f!1"), "f!1");
+    assert_eq!(quote_symbol("1f"), "|1f|");
+    assert_eq!(quote_symbol("a b"), "|a b|");
+    assert_eq!(quote_symbol("|a b|"), "|a b|");
+    assert_eq!(quote_symbol("a|b\\"), "|a\\|b\\\\|");
+
+    let log = "[mk-app] #1 a#b\n[mk-app] #2 |x,y|\n[mk-app] #3 f #1 #2\n[eof]\n";
+    let parser = parse(log);
+    let sexpr = parser.term_sexpr(TermIdx::from(2));
+    assert_eq!(sexpr, "(f |a#b| |x,y|)");
+    // Z3 logs quoted symbols with their bars, these are exported unchanged.
+    for symbol in ["|a#b|", "|x,y|"] {
+        let parser = parse(&format!("[mk-app] #1 {symbol}\n[eof]\n"));
+        assert_eq!(parser.term_sexpr(TermIdx::from(0)), symbol);
+    }
+}
+
+#[test]
+fn sexpr_binders() {
+    let log = "[mk-var] #1 0\n[mk-app] #2 f #1\n[mk-lambda] #3 <null> 1 #2\n\
+        [mk-quant] #4 q 1 #2\n[eof]\n";
+    let parser = parse(log);
+    assert_eq!(
+        parser.term_sexpr(TermIdx::from(2)),
+        "(lambda ((qvar_0 ?)) (f qvar_0))"
+    );
+    assert_eq!(
+        parser.term_sexpr(TermIdx::from(3)),
+        "(forall ((qvar_0 ?)) (f qvar_0))"
+    );
+}
+
+#[test]
+fn find_terms_by_pattern() {
+    use smt_log_parser::parsers::z3::term_pattern::{TermPattern, TermPatternError};
+    let log = "[mk-app] #1 a\n[mk-app] #2 b\n[mk-app] #3 g #1\n[mk-app] #4 f #1 #3\n[mk-app] #5 f #2 #3\n[eof]\n";
+    let parser = parse(log);
+    let find = |pattern: &str| {
+        parser
+            .find_terms_matching(&pattern.parse::<TermPattern>().unwrap())
+            .unwrap()
+    };
+    assert_eq!(find("(f ?x (g ?x))"), [TermIdx::from(3)]);
+    assert_eq!(find("(f ?x (g ?y))"), [TermIdx::from(3), TermIdx::from(4)]);
+    assert_eq!(find("(g a)"), [TermIdx::from(2)]);
+    assert_eq!(find("?x").len(), 5);
+
+    let err = |pattern: &str| pattern.parse::<TermPattern>().unwrap_err();
+    assert_eq!(err("(f ?x"), TermPatternError::UnexpectedEnd);
+    assert_eq!(
+        err("(?f a)"),
+        TermPatternError::ExpectedFunction("?f".to_string())
+    );
+    assert_eq!(err("a b"), TermPatternError::TrailingInput("b".to_string()));
+}